@@ -0,0 +1,29 @@
+use calamine::{open_workbook_auto, Reader}; // For reading Excel
+use anyhow::{Result};                       // For error handling
+use crate::model::ambient::AmbientEntry;    // output struct
+use crate::utils::{parse_number, parse_timestamp_ymd}; // helper
+
+// Loads the ambient temperature series from sheet "Temperatur", same layout as the load curve.
+pub fn load_ambient_temperature(path: &str) -> Result<Vec<AmbientEntry>> {
+
+    let mut workbook = open_workbook_auto(path)?;
+
+    let range = workbook.worksheet_range("Temperatur")?;
+
+    let mut entries = Vec::new();
+
+    for row in range.rows().skip(1) {
+
+        let timestamp = parse_timestamp_ymd(&row[0])?; //Row A
+
+        let temp_c = parse_number(&row[1])?; //Row B
+
+        entries.push(AmbientEntry {
+        timestamp,
+        temp_c,
+
+        });
+    }
+    Ok(entries)
+
+}
@@ -1,2 +1,6 @@
 pub mod load_importer;
 pub mod srl_importer;
+pub mod ambient_importer;
+pub mod reactive_importer;
+pub mod spot_importer;
+pub mod pv_importer;
@@ -0,0 +1,29 @@
+use calamine::{open_workbook_auto, Reader}; // For reading Excel
+use anyhow::{Result};                       // For error handling
+use crate::model::pv::PvEntry;              // output struct
+use crate::utils::{parse_number, parse_timestamp_ymd}; // helper
+
+// Loads the PV generation series from sheet "PV", same layout as the load curve.
+pub fn load_pv_generation(path: &str) -> Result<Vec<PvEntry>> {
+
+    let mut workbook = open_workbook_auto(path)?;
+
+    let range = workbook.worksheet_range("PV")?;
+
+    let mut entries = Vec::new();
+
+    for row in range.rows().skip(1) {
+
+        let timestamp = parse_timestamp_ymd(&row[0])?; //Row A
+
+        let pv_kw = parse_number(&row[1])?.max(0.0); //Row B, inverter night consumption ignored
+
+        entries.push(PvEntry {
+        timestamp,
+        pv_kw,
+
+        });
+    }
+    Ok(entries)
+
+}
@@ -0,0 +1,29 @@
+use calamine::{open_workbook_auto, Reader}; // For reading Excel
+use anyhow::{Result};                       // For error handling
+use crate::model::reactive::ReactiveEntry;  // output struct
+use crate::utils::{parse_number, parse_timestamp_ymd}; // helper
+
+// Loads the site reactive power series from sheet "Blindleistung", same layout as the load curve.
+pub fn load_reactive_power(path: &str) -> Result<Vec<ReactiveEntry>> {
+
+    let mut workbook = open_workbook_auto(path)?;
+
+    let range = workbook.worksheet_range("Blindleistung")?;
+
+    let mut entries = Vec::new();
+
+    for row in range.rows().skip(1) {
+
+        let timestamp = parse_timestamp_ymd(&row[0])?; //Row A
+
+        let reactive_kvar = parse_number(&row[1])?; //Row B
+
+        entries.push(ReactiveEntry {
+        timestamp,
+        reactive_kvar,
+
+        });
+    }
+    Ok(entries)
+
+}
@@ -0,0 +1,58 @@
+use calamine::{open_workbook_auto, DataType, Reader}; // For reading Excel
+use anyhow::{Result};                                 // For error handling
+use crate::model::spot::SpotPriceEntry;               // output struct
+use crate::utils::{parse_number, parse_timestamp_ymd}; // helper
+
+// Loads day-ahead spot prices (hourly or 15-min, EUR/MWh).
+// xlsx: sheet "Spotpreise", timestamp in column A, price in column B.
+// csv: header row, then timestamp (YYYY-MM-DD HH:MM) and price per line.
+pub fn load_spot_prices(path: &str) -> Result<Vec<SpotPriceEntry>> {
+
+    if path.to_lowercase().ends_with(".csv") {
+        return load_spot_prices_csv(path);
+    }
+
+    let mut workbook = open_workbook_auto(path)?;
+
+    let range = workbook.worksheet_range("Spotpreise")?;
+
+    let mut entries = Vec::new();
+
+    for row in range.rows().skip(1) {
+
+        let timestamp = parse_timestamp_ymd(&row[0])?; //Row A
+
+        let price_eur_mwh = parse_number(&row[1])?; //Row B
+
+        entries.push(SpotPriceEntry {
+        timestamp,
+        price_eur_mwh,
+
+        });
+    }
+    Ok(entries)
+
+}
+
+fn load_spot_prices_csv(path: &str) -> Result<Vec<SpotPriceEntry>> {
+
+    let mut rdr = csv::Reader::from_path(path)?;
+
+    let mut entries = Vec::new();
+
+    for record in rdr.records() {
+        let record = record?;
+
+        // Same parsing rules as the Excel cells
+        let timestamp = parse_timestamp_ymd(&DataType::String(record[0].to_string()))?;
+
+        let price_eur_mwh = parse_number(&DataType::String(record[1].to_string()))?;
+
+        entries.push(SpotPriceEntry {
+        timestamp,
+        price_eur_mwh,
+        });
+    }
+    Ok(entries)
+
+}
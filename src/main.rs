@@ -8,38 +8,68 @@ mod utils;
 use anyhow::Result;
 
 // CSV loading from Excel
+use excel::ambient_importer::load_ambient_temperature;
 use excel::load_importer::load_load_curve;
+use excel::pv_importer::load_pv_generation;
+use excel::reactive_importer::load_reactive_power;
+use excel::spot_importer::load_spot_prices;
 use excel::srl_importer::load_srl;
 
 // Interpolation tools
-use utils::interpolation::{generate_time_grid, interpolate_load_to_1min, interpolate_srl_to_1min};
+use utils::interpolation::{
+    generate_time_grid, interpolate_ambient_to_1min, interpolate_load_to_1min,
+    interpolate_pv_to_1min, interpolate_reactive_to_1min, interpolate_srl_to_1min,
+    resample_spot_to_1min,
+};
 
 // CSV export
 use utils::csv_export::save_to_csv;
-use utils::file_exists;
+use utils::{file_exists, is_newer};
 use utils::merging_csv::merge_1min_series;
 
 // Models
 use model::mergedseries::MergedTick;
 
 // simulation
+use simulation::auxiliary::AuxiliaryLoad;
 use simulation::config::SimulationConfig;
 use simulation::engine::run_simulation;
+use simulation::forecast::{evaluate_forecasts, print_accuracy};
+use simulation::optimizer::run_optimal;
+use simulation::reserve::SrlReserve;
+use simulation::strategy::mpc::run_mpc;
 use simulation::summary::summarize;
+use simulation::unit::BatteryUnitConfig;
 
 fn main() -> Result<()> {
     let merged_path = "data/output/merged_timeseries.csv";
     let merged_entries: Vec<MergedTick>;
 
-    if file_exists(merged_path) {
-        println!("Found existing merged_timeseries.csv — skipping import/interpolation.");
-        let mut rdr = csv::Reader::from_path(merged_path)?;
-        merged_entries = rdr.deserialize().collect::<Result<_, _>>()?;
+    let srl_path = "data/input/input_srl.xlsx";
+    let load_path = "data/input/input_wirkleistung.xlsx";
+    let ambient_path = "data/input/input_temperatur.xlsx";
+    let reactive_path = "data/input/input_blindleistung.xlsx";
+    let pv_path = "data/input/input_pv.xlsx";
+    // Day-ahead spot prices as Excel or CSV export
+    let spot_path = ["data/input/input_spotpreise.xlsx", "data/input/input_spotpreise.csv"]
+        .into_iter()
+        .find(|p| file_exists(p));
+
+    if let Some(cached) = read_merged_cache(
+        merged_path,
+        &[srl_path, load_path],
+        ambient_path,
+        reactive_path,
+        spot_path,
+        pv_path,
+    )? {
+        println!("Found up-to-date merged_timeseries.csv — skipping import/interpolation.");
+        merged_entries = cached;
     } else {
-        println!("Merged CSV not found. Running full pipeline...");
+        println!("Merged CSV missing or outdated. Running full pipeline...");
 
-        let srl_entries = load_srl("data/input/input_srl.xlsx")?;
-        let load_entries = load_load_curve("data/input/input_wirkleistung.xlsx")?;
+        let srl_entries = load_srl(srl_path)?;
+        let load_entries = load_load_curve(load_path)?;
 
         let (start, end) = (
             load_entries.first().unwrap().timestamp,
@@ -49,7 +79,56 @@ fn main() -> Result<()> {
         let time_grid = generate_time_grid(start, end, 1);
         let load_1min = interpolate_load_to_1min(&load_entries, &time_grid);
         let srl_1min = interpolate_srl_to_1min(&srl_entries, &time_grid);
-        merged_entries = merge_1min_series(&load_1min, &srl_1min);
+
+        // Ambient temperature is optional (auxiliary load, thermal model)
+        let ambient_1min = if file_exists(ambient_path) {
+            let ambient_entries = load_ambient_temperature(ambient_path)?;
+            let ambient_1min = interpolate_ambient_to_1min(&ambient_entries, &time_grid);
+            save_to_csv("data/output/ambient_cleaned.csv", &ambient_1min)?;
+            Some(ambient_1min)
+        } else {
+            None
+        };
+
+        // Reactive power is optional (apparent power at the transformer)
+        let reactive_1min = if file_exists(reactive_path) {
+            let reactive_entries = load_reactive_power(reactive_path)?;
+            let reactive_1min = interpolate_reactive_to_1min(&reactive_entries, &time_grid);
+            save_to_csv("data/output/reactive_cleaned.csv", &reactive_1min)?;
+            Some(reactive_1min)
+        } else {
+            None
+        };
+
+        // Day-ahead spot prices are optional (arbitrage)
+        let spot_1min = match spot_path {
+            Some(path) => {
+                let spot_entries = load_spot_prices(path)?;
+                let spot_1min = resample_spot_to_1min(&spot_entries, &time_grid);
+                save_to_csv("data/output/spot_cleaned.csv", &spot_1min)?;
+                Some(spot_1min)
+            }
+            None => None,
+        };
+
+        // PV generation is optional; with it the load curve is the gross consumption
+        let pv_1min = if file_exists(pv_path) {
+            let pv_entries = load_pv_generation(pv_path)?;
+            let pv_1min = interpolate_pv_to_1min(&pv_entries, &time_grid);
+            save_to_csv("data/output/pv_cleaned.csv", &pv_1min)?;
+            Some(pv_1min)
+        } else {
+            None
+        };
+
+        merged_entries = merge_1min_series(
+            &load_1min,
+            &srl_1min,
+            ambient_1min.as_deref(),
+            reactive_1min.as_deref(),
+            spot_1min.as_deref(),
+            pv_1min.as_deref(),
+        );
 
         save_to_csv("data/output/load_cleaned.csv", &load_1min)?;
         save_to_csv("data/output/srl_cleaned.csv", &srl_1min)?;
@@ -60,20 +139,123 @@ fn main() -> Result<()> {
         )
     }
     println!("Starting simulation");
-    let mut config = SimulationConfig::default();
+    let config = SimulationConfig {
+        units: vec![BatteryUnitConfig {
+            initial_soc_frac: 0.6,
+            self_discharge_pct_per_month: 2.0,
+            auxiliary_load: AuxiliaryLoad::Constant { kw: 3.0 },
+            ..Default::default()
+        }],
+        srl_reserve: SrlReserve::Symmetric { fraction: 0.2 },
+        ps_threshold_kw: 120.0,
+        ..Default::default()
+    };
 
-    config.initial_soc_frac = 0.6;
-    config.reserve_fraction = 0.2;
-    config.ps_threshold_kw = 120.0;
-
-    let sim_results = run_simulation(&merged_entries, &config);
+    // The MPC also reports its planned against the realised schedule
+    let (sim_run, mpc_report) = if config.strategy == "mpc" {
+        let (sim_run, mpc_report) = run_mpc(&merged_entries, &config);
+        save_to_csv("data/output/simulation.mpc.csv", &mpc_report.schedule)?;
+        println!("Exported to simulation.mpc.csv");
+        (sim_run, Some(mpc_report))
+    } else {
+        (run_simulation(&merged_entries, &config)?, None)
+    };
+    let sim_results = sim_run.ticks;
 
     save_to_csv("data/output/simulation.results.csv", &sim_results)?;
-    println!("Exported to simulation.results.csv");
+    save_to_csv("data/output/simulation.units.csv", &sim_run.units)?;
+    println!("Exported to simulation.results.csv and simulation.units.csv");
 
     let summary = summarize(&sim_results, &config);
     summary.print();
+    if let Some(mpc_report) = &mpc_report {
+        mpc_report.print();
+    }
+
+    save_to_csv("data/output/soh_daily.csv", &summary.soh_daily)?;
+    save_to_csv("data/output/soh_monthly.csv", &summary.soh_monthly)?;
+    println!("Exported to soh_daily.csv and soh_monthly.csv");
+
+    save_to_csv("data/output/cycle_histogram.csv", &summary.cycle_histogram)?;
+    save_to_csv("data/output/cycles_monthly.csv", &summary.cycles_monthly)?;
+    println!("Exported to cycle_histogram.csv and cycles_monthly.csv");
+
+    save_to_csv(
+        "data/output/transformer_episodes.csv",
+        &summary.transformer_episodes,
+    )?;
+    println!("Exported to transformer_episodes.csv");
+
+    // How well the forecast methods would have done on this series
+    if config.forecast_evaluation.enabled {
+        let accuracy = evaluate_forecasts(
+            &merged_entries,
+            &config.forecast_evaluation,
+            config.timestep_minutes,
+        );
+        print_accuracy(&accuracy);
+        save_to_csv("data/output/forecast_accuracy.csv", &accuracy)?;
+        println!("Exported to forecast_accuracy.csv");
+    }
+
+    // Perfect-foresight benchmark for the strategy above
+    if config.optimizer.enabled {
+        println!("Solving the perfect-foresight benchmark");
+        let optimal_run = run_optimal(&merged_entries, &config)?;
+        save_to_csv("data/output/simulation.optimal.csv", &optimal_run.ticks)?;
+        println!("Exported to simulation.optimal.csv");
+
+        println!("\nBenchmark (perfect foresight):");
+        summarize(&optimal_run.ticks, &config).print();
+    }
 
     println!("Simulation complete. Total ticks: {}", sim_results.len());
     Ok(())
 }
+
+/// Reads the merged series from the cache, unless it is outdated: an input changed after
+/// it was written, or an optional input exists whose column the cache does not have.
+fn read_merged_cache(
+    merged_path: &str,
+    required_paths: &[&str],
+    ambient_path: &str,
+    reactive_path: &str,
+    spot_path: Option<&str>,
+    pv_path: &str,
+) -> Result<Option<Vec<MergedTick>>> {
+    if !file_exists(merged_path) {
+        return Ok(None);
+    }
+    let optional = [Some(ambient_path), Some(reactive_path), spot_path, Some(pv_path)];
+    let changed = required_paths
+        .iter()
+        .copied()
+        .chain(optional.into_iter().flatten())
+        .filter(|p| file_exists(p))
+        .find(|p| is_newer(p, merged_path));
+    if let Some(path) = changed {
+        println!("{} changed since merged_timeseries.csv was written.", path);
+        return Ok(None);
+    }
+
+    let mut rdr = csv::Reader::from_path(merged_path)?;
+    let entries: Vec<MergedTick> = rdr.deserialize().collect::<Result<_, _>>()?;
+
+    // An optional input added after the cache was built has no column in it
+    let missing = |path: Option<&str>, has_column: fn(&MergedTick) -> bool| {
+        let missing = path.is_some_and(file_exists) && !entries.iter().any(has_column);
+        if missing {
+            println!("{} is not in merged_timeseries.csv yet.", path.unwrap_or_default());
+        }
+        missing
+    };
+    if missing(Some(ambient_path), |t| t.ambient_temp_c.is_some())
+        || missing(Some(reactive_path), |t| t.reactive_kvar.is_some())
+        || missing(spot_path, |t| t.spot_price_eur_mwh.is_some())
+        || missing(Some(pv_path), |t| t.pv_kw.is_some())
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(entries))
+}
@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AmbientEntry {
+    pub timestamp: DateTime<Utc>,
+    pub temp_c: f64,
+}
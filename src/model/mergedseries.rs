@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergedTick {
     pub timestamp: DateTime<Utc>,
     pub power_kw: f64,
@@ -9,4 +9,12 @@ pub struct MergedTick {
     pub srl_neg_kwh: f64,
     pub srl_pos_price_eur_mwh: f64,
     pub srl_neg_price_eur_mwh: f64,
+    #[serde(default)]
+    pub ambient_temp_c: Option<f64>, // only if an ambient series was imported
+    #[serde(default)]
+    pub reactive_kvar: Option<f64>, // only if a reactive power series was imported
+    #[serde(default)]
+    pub spot_price_eur_mwh: Option<f64>, // day-ahead price, only if a spot series was imported
+    #[serde(default)]
+    pub pv_kw: Option<f64>, // PV generation, already netted out of power_kw
 }
@@ -1,3 +1,7 @@
 pub mod srl;
 pub mod timeseries;
 pub mod mergedseries;
+pub mod ambient;
+pub mod reactive;
+pub mod spot;
+pub mod pv;
@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct PvEntry {
+    pub timestamp: DateTime<Utc>,
+    pub pv_kw: f64, // generation, >= 0
+}
@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ReactiveEntry {
+    pub timestamp: DateTime<Utc>,
+    pub reactive_kvar: f64, // + inductive (drawn from the grid)
+}
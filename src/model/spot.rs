@@ -0,0 +1,8 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SpotPriceEntry {
+    pub timestamp: DateTime<Utc>, // start of the delivery period
+    pub price_eur_mwh: f64,
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Container consumption (HVAC, BMS, inverter standby).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AuxiliaryLoad {
+    Constant {
+        kw: f64,
+    },
+    /// Base load plus cooling above / heating below a comfort band, driven by ambient temperature.
+    TemperatureDependent {
+        base_kw: f64,
+        cooling_kw_per_k: f64,
+        heating_kw_per_k: f64,
+        cooling_above_c: f64,
+        heating_below_c: f64,
+        fallback_ambient_c: f64, // used for ticks without ambient data
+    },
+}
+
+/// Where the auxiliary load is supplied from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AuxiliarySupply {
+    Battery, // battery first, grid covers whatever the battery cannot deliver
+    Grid,
+}
+
+impl AuxiliaryLoad {
+    pub fn power_kw(&self, ambient_temp_c: Option<f64>) -> f64 {
+        match *self {
+            AuxiliaryLoad::Constant { kw } => kw.max(0.0),
+            AuxiliaryLoad::TemperatureDependent {
+                base_kw,
+                cooling_kw_per_k,
+                heating_kw_per_k,
+                cooling_above_c,
+                heating_below_c,
+                fallback_ambient_c,
+            } => {
+                let t = ambient_temp_c.unwrap_or(fallback_ambient_c);
+                let cooling = (t - cooling_above_c).max(0.0) * cooling_kw_per_k;
+                let heating = (heating_below_c - t).max(0.0) * heating_kw_per_k;
+                (base_kw + cooling + heating).max(0.0)
+            }
+        }
+    }
+}
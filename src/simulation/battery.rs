@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+
+/// Which battery model the engine runs with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BatteryModelConfig {
+    /// kWh bucket, limited only by the kW rating.
+    Energy,
+    /// OCV + R0 + one RC pair, adds voltage and current limits.
+    EquivalentCircuit(EquivalentCircuitConfig),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquivalentCircuitConfig {
+    pub ocv_table: Vec<(f64, f64)>, // (soc_frac, pack open-circuit voltage in V)
+    pub r0_ohm: f64,                // series resistance
+    pub r1_ohm: f64,                // RC pair resistance
+    pub c1_farad: f64,              // RC pair capacitance
+    pub v_min: f64,
+    pub v_max: f64,
+    pub i_max_charge_a: f64,
+    pub i_max_discharge_a: f64,
+}
+
+impl Default for EquivalentCircuitConfig {
+    // LFP pack, 240 cells in series, ~1 MWh
+    fn default() -> Self {
+        let cells = 240.0;
+        Self {
+            ocv_table: [
+                (0.0, 2.90),
+                (0.05, 3.20),
+                (0.10, 3.25),
+                (0.30, 3.28),
+                (0.60, 3.30),
+                (0.90, 3.33),
+                (0.98, 3.40),
+                (1.0, 3.50),
+            ]
+            .iter()
+            .map(|&(soc, v)| (soc, v * cells))
+            .collect(),
+            r0_ohm: 0.012,
+            r1_ohm: 0.008,
+            c1_farad: 60_000.0,
+            v_min: 2.8 * cells,
+            v_max: 3.6 * cells,
+            i_max_charge_a: 1500.0,
+            i_max_discharge_a: 1500.0,
+        }
+    }
+}
+
+/// Electrical values of one tick; `None` for models without an electrical layer.
+pub struct ElectricalState {
+    pub voltage_v: Option<f64>,
+    pub current_a: Option<f64>, // + discharge, - charge
+}
+
+/// Battery abstraction used by the engine.
+///
+/// The engine keeps SoC bookkeeping in the `TickBudget`, a model adds its own
+/// power limits on top of the kW rating and reports the electrical state.
+pub trait BatteryModel {
+    /// DC-side (charge, discharge) power limits in kW for the coming tick.
+    fn power_limits_kw(
+        &self,
+        soc_kwh: f64,
+        capacity_kwh: f64,
+        resistance_factor: f64,
+    ) -> (f64, f64);
+
+    /// Advances the model by one tick given the SoC change the services caused.
+    fn step(
+        &mut self,
+        soc_before_kwh: f64,
+        soc_after_kwh: f64,
+        capacity_kwh: f64,
+        timestep_h: f64,
+        resistance_factor: f64,
+    ) -> ElectricalState;
+}
+
+pub fn build_battery_model(config: &BatteryModelConfig) -> Box<dyn BatteryModel> {
+    match config {
+        BatteryModelConfig::Energy => Box::new(EnergyBattery),
+        BatteryModelConfig::EquivalentCircuit(ecm) => Box::new(EquivalentCircuitBattery {
+            config: ecm.clone(),
+            v_rc: 0.0,
+        }),
+    }
+}
+
+pub struct EnergyBattery;
+
+impl BatteryModel for EnergyBattery {
+    fn power_limits_kw(&self, _: f64, _: f64, _: f64) -> (f64, f64) {
+        (f64::INFINITY, f64::INFINITY)
+    }
+
+    fn step(&mut self, _: f64, _: f64, _: f64, _: f64, _: f64) -> ElectricalState {
+        ElectricalState {
+            voltage_v: None,
+            current_a: None,
+        }
+    }
+}
+
+pub struct EquivalentCircuitBattery {
+    config: EquivalentCircuitConfig,
+    v_rc: f64, // voltage over the RC pair
+}
+
+impl EquivalentCircuitBattery {
+    fn ocv(&self, soc_frac: f64) -> f64 {
+        let table = &self.config.ocv_table;
+        let (first, last) = match (table.first(), table.last()) {
+            (Some(f), Some(l)) => (f, l),
+            _ => return 0.0,
+        };
+
+        if soc_frac <= first.0 {
+            return first.1;
+        }
+        if soc_frac >= last.0 {
+            return last.1;
+        }
+
+        for pair in table.windows(2) {
+            let (s0, v0) = pair[0];
+            let (s1, v1) = pair[1];
+            if soc_frac <= s1 && (s1 - s0).abs() > 1e-9 {
+                return v0 + (soc_frac - s0) / (s1 - s0) * (v1 - v0);
+            }
+        }
+
+        last.1
+    }
+
+    /// Current for a DC power (+ discharge) from P = (E - I * R0) * I.
+    fn current_for_power(&self, e: f64, r0: f64, power_w: f64) -> f64 {
+        let disc = (e * e - 4.0 * r0 * power_w).max(0.0);
+        (e - disc.sqrt()) / (2.0 * r0)
+    }
+}
+
+impl BatteryModel for EquivalentCircuitBattery {
+    fn power_limits_kw(
+        &self,
+        soc_kwh: f64,
+        capacity_kwh: f64,
+        resistance_factor: f64,
+    ) -> (f64, f64) {
+        let cfg = &self.config;
+        let r0 = cfg.r0_ohm * resistance_factor;
+        // Source voltage behind R0
+        let e = self.ocv(soc_kwh / capacity_kwh) - self.v_rc;
+
+        // Discharge: current limit, terminal voltage >= v_min, and max power point
+        let i_dis = cfg
+            .i_max_discharge_a
+            .min(((e - cfg.v_min) / r0).max(0.0))
+            .min(e / (2.0 * r0));
+        let p_dis_w = (e - i_dis * r0) * i_dis;
+
+        // Charge: current limit and terminal voltage <= v_max
+        let i_ch = cfg.i_max_charge_a.min(((cfg.v_max - e) / r0).max(0.0));
+        let p_ch_w = (e + i_ch * r0) * i_ch;
+
+        (p_ch_w / 1000.0, p_dis_w / 1000.0)
+    }
+
+    fn step(
+        &mut self,
+        soc_before_kwh: f64,
+        soc_after_kwh: f64,
+        capacity_kwh: f64,
+        timestep_h: f64,
+        resistance_factor: f64,
+    ) -> ElectricalState {
+        let cfg = &self.config;
+        let r0 = cfg.r0_ohm * resistance_factor;
+        let r1 = cfg.r1_ohm * resistance_factor;
+
+        let power_w = (soc_before_kwh - soc_after_kwh) / timestep_h * 1000.0;
+        let e = self.ocv(soc_before_kwh / capacity_kwh) - self.v_rc;
+        let current_a = self.current_for_power(e, r0, power_w);
+        let voltage_v = e - current_a * r0;
+
+        // RC pair relaxes towards R1 * I
+        let tau_s = r1 * cfg.c1_farad;
+        let decay = (-(timestep_h * 3600.0) / tau_s).exp();
+        self.v_rc = self.v_rc * decay + r1 * current_a * (1.0 - decay);
+
+        ElectricalState {
+            voltage_v: Some(voltage_v),
+            current_a: Some(current_a),
+        }
+    }
+}
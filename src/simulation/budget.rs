@@ -0,0 +1,126 @@
+/// Per-tick power and energy budget shared by all services.
+///
+/// Every service draws grid-side energy from the same budget in priority order.
+/// The budget tracks the net converter power already committed in this tick and
+/// the live SoC, so a later service can never exceed what an earlier one left over.
+pub struct TickBudget {
+    soc_kwh: f64,
+    soc_min: f64,
+    soc_max: f64,
+    e_charge_max: f64,    // charge energy per tick (derated power * timestep_h)
+    e_discharge_max: f64, // discharge energy per tick (derated power * timestep_h)
+    net_out_kwh: f64,     // committed net discharge (+) / charge (-) in this tick
+    eff: f64,
+    losses_kwh: f64, // conversion losses of everything drawn so far
+
+    // Ramp window for the net energy of this tick, relative to the previous tick
+    ramp_min_kwh: f64,
+    ramp_max_kwh: f64,
+}
+
+impl TickBudget {
+    pub fn new(
+        soc_kwh: f64,
+        soc_min: f64,
+        soc_max: f64,
+        p_charge_max_kw: f64,
+        p_discharge_max_kw: f64,
+        timestep_h: f64,
+        eff: f64,
+    ) -> Self {
+        Self {
+            soc_kwh,
+            soc_min,
+            soc_max,
+            e_charge_max: p_charge_max_kw * timestep_h,
+            e_discharge_max: p_discharge_max_kw * timestep_h,
+            net_out_kwh: 0.0,
+            eff,
+            losses_kwh: 0.0,
+            ramp_min_kwh: f64::NEG_INFINITY,
+            ramp_max_kwh: f64::INFINITY,
+        }
+    }
+
+    /// Restricts the net battery power (+ discharge) of this tick to `[min_kw, max_kw]`.
+    pub fn with_ramp_window(mut self, min_kw: f64, max_kw: f64, timestep_h: f64) -> Self {
+        self.ramp_min_kwh = min_kw * timestep_h;
+        self.ramp_max_kwh = max_kw * timestep_h;
+        self
+    }
+
+    /// Grid-side energy that can still be discharged without going below `floor_kwh`.
+    pub fn available_discharge_kwh(&self, floor_kwh: f64) -> f64 {
+        let ramp_left = (self.ramp_max_kwh - self.net_out_kwh).max(0.0);
+        self.unramped_discharge_kwh(floor_kwh).min(ramp_left)
+    }
+
+    /// Grid-side energy that can still be charged without going above `ceiling_kwh`.
+    pub fn available_charge_kwh(&self, ceiling_kwh: f64) -> f64 {
+        let ramp_left = (self.net_out_kwh - self.ramp_min_kwh).max(0.0);
+        self.unramped_charge_kwh(ceiling_kwh).min(ramp_left)
+    }
+
+    /// Like `available_discharge_kwh`, ignoring the ramp window.
+    pub fn unramped_discharge_kwh(&self, floor_kwh: f64) -> f64 {
+        let power_left = (self.e_discharge_max - self.net_out_kwh).max(0.0);
+        let energy_left = (self.soc_kwh - floor_kwh.max(self.soc_min)).max(0.0) * self.eff;
+        power_left.min(energy_left)
+    }
+
+    /// Like `available_charge_kwh`, ignoring the ramp window.
+    pub fn unramped_charge_kwh(&self, ceiling_kwh: f64) -> f64 {
+        let power_left = (self.e_charge_max + self.net_out_kwh).max(0.0);
+        let energy_left = (ceiling_kwh.min(self.soc_max) - self.soc_kwh).max(0.0) / self.eff;
+        power_left.min(energy_left)
+    }
+
+    /// Discharges up to `requested_kwh` (grid side) and returns the delivered energy.
+    pub fn discharge(&mut self, requested_kwh: f64, floor_kwh: f64) -> f64 {
+        let fulfilled = requested_kwh
+            .max(0.0)
+            .min(self.available_discharge_kwh(floor_kwh));
+        self.net_out_kwh += fulfilled;
+        self.soc_kwh -= fulfilled / self.eff;
+        self.losses_kwh += fulfilled / self.eff - fulfilled;
+        fulfilled
+    }
+
+    /// Charges up to `requested_kwh` (grid side) and returns the absorbed energy.
+    pub fn charge(&mut self, requested_kwh: f64, ceiling_kwh: f64) -> f64 {
+        let fulfilled = requested_kwh
+            .max(0.0)
+            .min(self.available_charge_kwh(ceiling_kwh));
+        self.net_out_kwh -= fulfilled;
+        self.soc_kwh += fulfilled * self.eff;
+        self.losses_kwh += fulfilled - fulfilled * self.eff;
+        fulfilled
+    }
+
+    /// Keeps the net power inside the ramp window once all services are served.
+    /// If the services ask for a faster change than allowed, the battery holds its
+    /// power at the window edge (as far as SoC allows). Returns the held energy (+ discharge).
+    pub fn hold_ramp_window(&mut self) -> f64 {
+        if self.net_out_kwh < self.ramp_min_kwh {
+            let missing = self.ramp_min_kwh - self.net_out_kwh;
+            self.discharge(missing, self.soc_min)
+        } else if self.net_out_kwh > self.ramp_max_kwh {
+            let excess = self.net_out_kwh - self.ramp_max_kwh;
+            -self.charge(excess, self.soc_max)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn soc_kwh(&self) -> f64 {
+        self.soc_kwh
+    }
+
+    pub fn losses_kwh(&self) -> f64 {
+        self.losses_kwh
+    }
+
+    pub fn net_out_kwh(&self) -> f64 {
+        self.net_out_kwh
+    }
+}
@@ -1,34 +1,118 @@
+use super::auxiliary::AuxiliarySupply;
+use super::degradation::SohEntry;
+use super::fleet::Allocator;
+use super::forecast::ForecastEvaluationConfig;
+use super::optimizer::OptimizerConfig;
+use super::priority::ServicePriority;
+use super::pv::{PvConfig, PvKpis};
+use super::rainflow::{CycleBin, MonthlyCycles};
+use super::recovery::SocRecoveryConfig;
+use super::reserve::SrlReserve;
+use super::strategy::adaptive_peak_shaving::AdaptivePeakShavingConfig;
+use super::strategy::arbitrage::ArbitrageConfig;
+use super::strategy::mpc::MpcConfig;
+use super::strategy::time_of_use::TimeOfUseConfig;
+use super::tariff::TariffCalendar;
+use super::transformer::{TransformerThermalConfig, ViolationEpisode};
+use super::unit::BatteryUnitConfig;
+use crate::model::mergedseries::MergedTick;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
-    pub capacity_kwh: f64,
-    pub c_rate: f64,
-    pub efficiency: f64,           // 0.95 → 95%
-    pub min_soc_frac: f64,         // e.g. 0.1
-    pub initial_soc_frac: f64,     // e.g. 0.5
-    pub reserve_fraction: f64,     // e.g. 0.3 = 30% reserved for SRL
-    pub transformer_limit_kw: f64, // e.g. 240.0
-    pub timestep_minutes: f64,     // usually 1.0
+    pub units: Vec<BatteryUnitConfig>, // battery units behind the grid connection
+    pub allocator: Allocator,          // how service requests are split across units
+    pub srl_reserve: SrlReserve,       // SoC kept free for SRL pos / neg
+    pub transformer_limit_kva: f64,    // e.g. 240.0, checked against apparent power
+    pub timestep_minutes: f64,         // usually 1.0
     pub battery_price_per_kwh_chf: f64,
     pub operating_cost_rate: f64,
     pub ps_threshold_kw: f64,
+    pub auxiliary_supply: AuxiliarySupply,
+    pub grid_energy_price_chf_per_kwh: f64, // for auxiliary consumption
+    pub enforce_transformer_limit: bool, // curtail services and relieve the load to stay within it
+    pub site_power_factor: Option<f64>,  // used when no reactive power series was imported
+    pub reactive_compensation: bool,     // inverters supply reactive power within their kVA
+    pub reactive_energy_price_chf_per_kvarh: f64, // value of compensated reactive energy
+    pub transformer_thermal: TransformerThermalConfig,
+    pub strategy: String, // dispatch strategy by name, see strategy::build_strategy
+    pub arbitrage: ArbitrageConfig,
+    pub pv: PvConfig,                   // feed-in limit, only used with a PV series
+    pub tariff: Option<TariffCalendar>, // HT/NT energy tariff, enables energy cost savings
+    pub time_of_use: TimeOfUseConfig,
+    pub adaptive_peak_shaving: AdaptivePeakShavingConfig,
+    pub demand_charge_chf_per_kw_month: f64, // on the monthly grid peak
+    pub optimizer: OptimizerConfig,          // perfect-foresight benchmark
+    pub mpc: MpcConfig,
+    pub forecast_evaluation: ForecastEvaluationConfig, // accuracy of the forecast methods
+    pub soc_recovery: SocRecoveryConfig,               // toward a target band in idle ticks
+    pub service_priority: ServicePriority,             // SRL or peak shaving first
 }
 
 impl Default for SimulationConfig {
     fn default() -> Self {
         Self {
-            capacity_kwh: 1000.0,
-            c_rate: 1.0,
-            efficiency: 0.95,
-            min_soc_frac: 0.1,
-            initial_soc_frac: 0.5,
-            reserve_fraction: 0.5,
-            transformer_limit_kw: 240.0,
+            units: vec![BatteryUnitConfig::default()],
+            allocator: Allocator::ProportionalToCapacity,
+            srl_reserve: SrlReserve::Symmetric { fraction: 0.5 },
+            transformer_limit_kva: 240.0,
             timestep_minutes: 1.0,
             battery_price_per_kwh_chf: 400.0,
             operating_cost_rate: 0.01,
             ps_threshold_kw: 180.0,
+            auxiliary_supply: AuxiliarySupply::Grid,
+            grid_energy_price_chf_per_kwh: 0.20,
+            enforce_transformer_limit: true,
+            site_power_factor: None,
+            reactive_compensation: true,
+            reactive_energy_price_chf_per_kvarh: 0.0,
+            transformer_thermal: TransformerThermalConfig::default(),
+            strategy: "srl_peak_shaving".to_string(),
+            arbitrage: ArbitrageConfig::default(),
+            pv: PvConfig::default(),
+            tariff: None,
+            time_of_use: TimeOfUseConfig::default(),
+            adaptive_peak_shaving: AdaptivePeakShavingConfig::default(),
+            demand_charge_chf_per_kw_month: 10.0,
+            optimizer: OptimizerConfig::default(),
+            mpc: MpcConfig::default(),
+            forecast_evaluation: ForecastEvaluationConfig::default(),
+            soc_recovery: SocRecoveryConfig::default(),
+            service_priority: ServicePriority::SrlFirst,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Nameplate capacity of the whole fleet.
+    pub fn capacity_kwh(&self) -> f64 {
+        self.units.iter().map(|u| u.capacity_kwh).sum()
+    }
+
+    /// Nominal one-way efficiency of the fleet, weighted by nameplate.
+    pub fn efficiency(&self) -> f64 {
+        self.units
+            .iter()
+            .map(|u| u.efficiency * u.capacity_kwh)
+            .sum::<f64>()
+            / self.capacity_kwh()
+    }
+
+    /// Site reactive power: measured series, else derived from the configured power factor.
+    pub fn site_reactive_kvar(&self, tick: &MergedTick) -> f64 {
+        tick.reactive_kvar.unwrap_or_else(|| {
+            self.site_power_factor.map_or(0.0, |pf| {
+                tick.power_kw.abs() * (1.0 / (pf * pf) - 1.0).max(0.0).sqrt()
+            })
+        })
+    }
+
+    /// Energy price of grid import: the tariff calendar if set, else the flat price.
+    pub fn energy_price_chf_per_kwh(&self, timestamp: DateTime<Utc>) -> f64 {
+        match &self.tariff {
+            Some(tariff) => tariff.price_chf_per_kwh(timestamp),
+            None => self.grid_energy_price_chf_per_kwh,
         }
     }
 }
@@ -42,6 +126,32 @@ pub struct SimulationSummary {
     pub total_srl_in_kwh: f64,
     pub total_ps_out_kwh: f64,
     pub total_ps_in_kwh: f64,
+    pub total_tou_out_kwh: f64, // time-of-use shifting
+    pub total_tou_in_kwh: f64,
+    pub total_arbitrage_out_kwh: f64,
+    pub total_arbitrage_in_kwh: f64,
+    pub total_recovery_in_kwh: f64, // SoC recovery in idle ticks
+    pub total_recovery_out_kwh: f64,
+
+    // Requested but not delivered
+    pub total_srl_unmet_kwh: f64,
+    pub total_ps_unmet_kwh: f64,
+    pub total_srl_ramp_shortfall_kwh: f64, // part of unmet caused by ramp limits
+    pub total_ps_ramp_shortfall_kwh: f64,
+
+    // Reactive compensation
+    pub total_reactive_compensation_kvarh: f64,
+
+    // Losses and auxiliary consumption
+    pub total_self_discharge_kwh: f64,
+    pub total_aux_kwh: f64,
+    pub total_aux_grid_kwh: f64,
+
+    // Thermal
+    pub max_cell_temp_c: f64,
+    pub min_cell_temp_c: f64,
+    pub total_hvac_kwh: f64,
+    pub peak_hvac_kw: f64,
 
     // SoC extremes
     pub min_soc_kwh: f64,
@@ -49,12 +159,42 @@ pub struct SimulationSummary {
 
     // transformer
     pub transformer_violations: usize,
+    pub transformer_excess_kvah: f64, // apparent energy above the limit that remained
+    pub peak_grid_apparent_kva: f64,
+    pub transformer_episodes: Vec<ViolationEpisode>,
+    pub peak_transformer_hot_spot_c: Option<f64>,
+    pub transformer_loss_of_life_h: f64, // aging hours at the reference hot spot
+    pub total_transformer_relief_kwh: f64,
+    pub total_srl_curtailed_kwh: f64,
+    pub total_ps_curtailed_kwh: f64,
+
+    // SRL and peak shaving competing
+    pub competing_ticks: usize,
+    pub total_opportunity_cost_chf: f64,
+
+    // Self-consumption, only with a PV series
+    pub pv: Option<PvKpis>,
 
     // Economics -> to be expanded
     pub total_srl_revenue_chf: f64,
     pub peak_shaving_savings_chf: f64,
+    pub energy_cost_chf: f64, // site import at the tariff, only with a tariff calendar
+    pub energy_cost_without_battery_chf: f64,
+    pub energy_cost_savings_chf: f64,
+    pub arbitrage_revenue_chf: f64,
+    pub recovery_cost_chf: f64, // grid energy of the SoC recovery, net of what it saves
+    pub aux_cost_chf: f64,
+    pub reactive_savings_chf: f64,
     pub amortization_years: Option<f64>,
 
-    // Battery wear / Cycles
+    // Battery wear / Cycles (rainflow, equivalent full cycles against nameplate)
     pub battery_cycles: f64,
+    pub cycle_histogram: Vec<CycleBin>,
+    pub cycles_monthly: Vec<MonthlyCycles>,
+
+    // Degradation
+    pub final_soh: f64,
+    pub soh_daily: Vec<SohEntry>,
+    pub soh_monthly: Vec<SohEntry>,
+    pub projected_end_of_life: Option<NaiveDate>,
 }
@@ -0,0 +1,170 @@
+use super::rainflow::{RainflowCounter, RainflowCycle};
+use serde::{Deserialize, Serialize};
+
+/// State of health at the end of a reporting period (day or month).
+#[derive(Debug, Serialize)]
+pub struct SohEntry {
+    pub period: String,
+    pub soh: f64,
+    pub resistance_factor: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DegradationConfig {
+    pub enabled: bool,
+
+    // Cycle aging
+    pub cycle_fade_per_cycle: f64, // capacity loss per full cycle at reference DoD / C-rate, e.g. 0.2 / 6000
+    pub reference_dod: f64,        // DoD the fade rate refers to, e.g. 1.0
+    pub dod_exponent: f64,         // stress ∝ (dod / reference_dod)^k
+    pub reference_c_rate: f64,     // C-rate without extra stress
+    pub c_rate_stress: f64,        // extra stress per C above reference_c_rate
+
+    // Calendar aging
+    pub calendar_fade_per_year: f64, // at 50% SoC and reference temperature
+    pub calendar_soc_stress: f64,    // stress factor 1 + k * (soc - 0.5)
+    pub reference_temp_c: f64,
+    pub temp_doubling_k: f64, // calendar rate doubles every k Kelvin (Arrhenius rule of thumb)
+    pub cell_temp_c: f64,     // assumed constant cell temperature
+
+    // Resistance and end of life
+    pub resistance_growth_per_fade: f64, // relative resistance rise per unit of capacity fade
+    pub eol_soh: f64,                    // e.g. 0.8 = 80% of nameplate
+}
+
+impl Default for DegradationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cycle_fade_per_cycle: 0.2 / 6000.0,
+            reference_dod: 1.0,
+            dod_exponent: 1.5,
+            reference_c_rate: 0.5,
+            c_rate_stress: 0.5,
+            calendar_fade_per_year: 0.015,
+            calendar_soc_stress: 1.0,
+            reference_temp_c: 25.0,
+            temp_doubling_k: 10.0,
+            cell_temp_c: 25.0,
+            resistance_growth_per_fade: 2.0,
+            eol_soh: 0.8,
+        }
+    }
+}
+
+/// Throughput and time under load up to a SoC turning point, for the C-rate of a cycle.
+#[derive(Debug, Clone, Copy, Default)]
+struct CycleMark {
+    throughput_kwh: f64,
+    hours: f64, // ticks in which the SoC moved
+}
+
+/// Aging state of one battery.
+///
+/// Cycle aging follows the rainflow count of the SoC: every closed cycle costs capacity
+/// by depth and mean C-rate, the open residue counts as half cycles until it closes, so
+/// the last partial cycle is in the SoH as well. Calendar aging accrues every tick
+/// depending on SoC and temperature.
+pub struct DegradationState {
+    config: DegradationConfig,
+    nameplate_kwh: f64,
+
+    cycle_fade: f64,   // closed cycles
+    residue_fade: f64, // open half cycles, recounted every tick
+    calendar_fade: f64,
+
+    cycles: RainflowCounter<CycleMark>,
+    mark: CycleMark,
+}
+
+impl DegradationState {
+    pub fn new(config: &DegradationConfig, nameplate_kwh: f64, initial_soc_kwh: f64) -> Self {
+        let mut cycles = RainflowCounter::default();
+        cycles.push(initial_soc_kwh, CycleMark::default());
+
+        Self {
+            config: config.clone(),
+            nameplate_kwh,
+            cycle_fade: 0.0,
+            residue_fade: 0.0,
+            calendar_fade: 0.0,
+            cycles,
+            mark: CycleMark::default(),
+        }
+    }
+
+    /// State of health as fraction of nameplate capacity.
+    pub fn soh(&self) -> f64 {
+        (1.0 - self.cycle_fade - self.residue_fade - self.calendar_fade).max(0.0)
+    }
+
+    /// Usable capacity after fade.
+    pub fn capacity_kwh(&self) -> f64 {
+        self.nameplate_kwh * self.soh()
+    }
+
+    /// Internal resistance relative to the new battery.
+    pub fn resistance_factor(&self) -> f64 {
+        1.0 + self.config.resistance_growth_per_fade * (1.0 - self.soh())
+    }
+
+    /// One-way efficiency with losses scaled by the resistance rise.
+    pub fn efficiency(&self, nominal_eff: f64) -> f64 {
+        (1.0 - (1.0 - nominal_eff) * self.resistance_factor()).max(0.0)
+    }
+
+    /// Advances the aging state by one tick.
+    pub fn update(
+        &mut self,
+        soc_before_kwh: f64,
+        soc_after_kwh: f64,
+        timestep_h: f64,
+        cell_temp_c: f64,
+    ) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let cfg = &self.config;
+
+        // Calendar aging
+        let soc_frac = soc_after_kwh / self.nameplate_kwh;
+        let soc_stress = (1.0 + cfg.calendar_soc_stress * (soc_frac - 0.5)).max(0.0);
+        let temp_stress = 2f64.powf((cell_temp_c - cfg.reference_temp_c) / cfg.temp_doubling_k);
+        self.calendar_fade +=
+            cfg.calendar_fade_per_year * timestep_h / 8760.0 * soc_stress * temp_stress;
+
+        // Cycle aging
+        let delta = soc_after_kwh - soc_before_kwh;
+        if delta.abs() > 1e-9 {
+            self.mark.throughput_kwh += delta.abs();
+            self.mark.hours += timestep_h;
+        }
+
+        for cycle in self.cycles.push(soc_after_kwh, self.mark) {
+            self.cycle_fade += self.fade(&cycle);
+        }
+        self.residue_fade = self
+            .cycles
+            .residue()
+            .iter()
+            .map(|cycle| self.fade(cycle))
+            .sum();
+    }
+
+    /// Capacity a (half) cycle costs, by depth and mean C-rate.
+    fn fade(&self, cycle: &RainflowCycle<CycleMark>) -> f64 {
+        let cfg = &self.config;
+        let dod = cycle.range / self.nameplate_kwh;
+        let hours = cycle.end.hours - cycle.start.hours;
+        let c_rate = if hours > 0.0 {
+            (cycle.end.throughput_kwh - cycle.start.throughput_kwh) / hours / self.nameplate_kwh
+        } else {
+            0.0
+        };
+        let dod_stress = (dod / cfg.reference_dod).powf(cfg.dod_exponent);
+        let c_stress = 1.0 + cfg.c_rate_stress * (c_rate - cfg.reference_c_rate).max(0.0);
+
+        cycle.count * cfg.cycle_fade_per_cycle * dod_stress * c_stress
+    }
+}
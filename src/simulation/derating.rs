@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Piecewise-linear power derating curve, over SoC (or cell temperature).
+/// Points are (x, power_frac) pairs sorted by x, linearly interpolated in between.
+/// Outside the first/last point the edge value is held.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DeratingCurve {
+    pub points: Vec<(f64, f64)>,
+}
+
+impl DeratingCurve {
+    /// Typical CC/CV charge taper: full power up to 90% SoC, down to 20% at full.
+    pub fn default_charge() -> Self {
+        Self {
+            points: vec![(0.0, 1.0), (0.9, 1.0), (1.0, 0.2)],
+        }
+    }
+
+    /// Discharge taper near the bottom of the SoC window.
+    pub fn default_discharge() -> Self {
+        Self {
+            points: vec![(0.0, 0.2), (0.15, 1.0), (1.0, 1.0)],
+        }
+    }
+
+    /// Power factor (0..1) at the given SoC fraction (or temperature).
+    pub fn factor(&self, x: f64) -> f64 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(f), Some(l)) => (f, l),
+            _ => return 1.0,
+        };
+
+        if x <= first.0 {
+            return first.1.clamp(0.0, 1.0);
+        }
+        if x >= last.0 {
+            return last.1.clamp(0.0, 1.0);
+        }
+
+        for pair in self.points.windows(2) {
+            let (s0, f0) = pair[0];
+            let (s1, f1) = pair[1];
+
+            if x >= s0 && x <= s1 {
+                if (s1 - s0).abs() < 1e-9 {
+                    return f0.clamp(0.0, 1.0);
+                }
+                let alpha = (x - s0) / (s1 - s0);
+                return (f0 + alpha * (f1 - f0)).clamp(0.0, 1.0);
+            }
+        }
+
+        1.0
+    }
+}
@@ -1,104 +1,457 @@
 use super::config::SimulationConfig;
-use super::tick_result::SimulationTickResult;
+use super::fleet::FleetBudget;
+use super::priority::{Competition, Service};
+use super::strategy::{DispatchContext, DispatchStrategy, build_strategy};
+use super::tick_result::{SimulationTickResult, UnitTickResult};
+use super::transformer::TransformerThermalState;
+use super::unit::{BatteryUnit, ServiceBand};
 use crate::model::mergedseries::MergedTick;
+use anyhow::Result;
+use chrono::Datelike;
 
-pub fn run_simulation(
+/// Aggregate results of the fleet plus the results of every unit.
+pub struct SimulationRun {
+    pub ticks: Vec<SimulationTickResult>,
+    pub units: Vec<UnitTickResult>,
+}
+
+/// Runs the strategy named in the config.
+pub fn run_simulation(ticks: &[MergedTick], config: &SimulationConfig) -> Result<SimulationRun> {
+    let mut strategy = build_strategy(config)?;
+    Ok(run_simulation_with(ticks, config, strategy.as_mut()))
+}
+
+/// Runs any strategy, e.g. a site-specific one that is not registered by name.
+pub fn run_simulation_with(
     ticks: &[MergedTick],
     config: &SimulationConfig,
-) -> Vec<SimulationTickResult> {
+    strategy: &mut dyn DispatchStrategy,
+) -> SimulationRun {
     let mut results = Vec::with_capacity(ticks.len());
+    let mut unit_results = Vec::with_capacity(ticks.len() * config.units.len());
 
-    // === Init battery state ===
-    let soc_min = config.capacity_kwh * config.min_soc_frac;
-    let soc_max = config.capacity_kwh;
-    let soc_reserve = (soc_max - soc_min) * config.reserve_fraction;
-    let mut soc_kwh = config.capacity_kwh * config.initial_soc_frac;
+    // === Init battery units ===
+    let mut units: Vec<BatteryUnit> = config.units.iter().map(BatteryUnit::new).collect();
+    let capacities_kwh: Vec<f64> = config.units.iter().map(|u| u.capacity_kwh).collect();
+    let nameplate_kwh = config.capacity_kwh();
+    let mut transformer_thermal = TransformerThermalState::new(&config.transformer_thermal);
 
     // === Constants ===
     let timestep_h = config.timestep_minutes / 60.0;
-    let p_max = config.capacity_kwh * config.c_rate;
-    let eff = config.efficiency;
+    let efficiency = config.efficiency();
+    let enforced_limit_kva = if config.enforce_transformer_limit {
+        config.transformer_limit_kva
+    } else {
+        f64::INFINITY
+    };
+
+    // Day boundaries for the day-ahead prices handed to the strategy
+    let spot_prices: Vec<Option<f64>> = ticks.iter().map(|t| t.spot_price_eur_mwh).collect();
+    let mut day_start = 0;
+    let mut day_end = 0;
+
+    // PV feed-in limit, from the configured peak or the peak of the series
+    let pv_peak_kw = ticks.iter().filter_map(|t| t.pv_kw).fold(0.0, f64::max);
+    let feed_in_limit_kw = config.pv.feed_in_limit_kw(pv_peak_kw);
+
+    // Running grid peak of the billing month, for the value of peak shaving
+    let mut month = None;
+    let mut month_peak_kw: f64 = 0.0;
 
     // Debug counters
     let mut srl_pos_count = 0;
     let mut srl_neg_count = 0;
 
-    for tick in ticks {
-        // SRL response
-        let mut srl_energy_in_kwh = 0.0;
-        let mut srl_energy_out_kwh = 0.0;
-
-        // max available discharge / charge
-        let max_discharge_kwh = ((soc_kwh - soc_min - soc_reserve).max(0.0)) * eff;
-        let max_charge_kwh = ((soc_max - soc_kwh - soc_reserve).max(0.0)) / eff;
-        let e_max = p_max * timestep_h;
-
-        // Discharge for SRL_pos
-        if tick.srl_pos_kwh > 0.0 {
-            srl_pos_count += 1;
-
-            let requested = tick.srl_pos_kwh;
-            let possible = max_discharge_kwh.min(e_max);
-            let fulfilled = requested.min(possible);
-
-            srl_energy_out_kwh = fulfilled;
-            soc_kwh -= fulfilled / eff;
+    for (i, tick) in ticks.iter().enumerate() {
+        // Every unit fixes its limits and serves its own auxiliary load first.
+        // The grid covers what aux does not get.
+        let unit_ticks = units
+            .iter_mut()
+            .map(|unit| {
+                unit.begin_tick(
+                    tick.ambient_temp_c,
+                    &config.srl_reserve,
+                    unit.config.capacity_kwh / nameplate_kwh,
+                    config.auxiliary_supply,
+                    timestep_h,
+                )
+            })
+            .collect();
+
+        // Services draw from the fleet in priority order: transformer relief, then SRL and peak
+        // shaving as `service_priority` says, then time-of-use shifting and arbitrage.
+        // SRL may use the reserve band, peak shaving must leave it untouched.
+        let mut fleet = FleetBudget::new(unit_ticks, capacities_kwh.clone(), config.allocator);
+
+        let site_reactive_kvar = config.site_reactive_kvar(tick);
+
+        // Active power the transformer leaves next to the uncompensated reactive power.
+        // Compensation by the inverters can only lower |Q|, so this is on the safe side.
+        let enforced_limit_kw = (enforced_limit_kva.powi(2) - site_reactive_kvar.powi(2))
+            .max(0.0)
+            .sqrt();
+
+        // Grid power so far (+ import); services may only move it within the transformer limit
+        let mut grid_kw = tick.power_kw + fleet.aux_grid_kw();
+        let import_headroom_kwh =
+            |grid_kw: f64| (enforced_limit_kw - grid_kw).max(0.0) * timestep_h;
+        let export_headroom_kwh =
+            |grid_kw: f64| (enforced_limit_kw + grid_kw).max(0.0) * timestep_h;
+        // Charging on the battery's own account must not set a new peak either
+        let threshold_headroom_kwh =
+            |grid_kw: f64| (config.ps_threshold_kw - grid_kw).max(0.0) * timestep_h;
+
+        // Transformer relief: if the load alone exceeds the limit, the battery has to step in
+        // before any service, using the whole SoC window.
+        let mut transformer_relief_kw = 0.0;
+        if grid_kw > enforced_limit_kw {
+            let requested_kwh = (grid_kw - enforced_limit_kw) * timestep_h;
+            transformer_relief_kw = fleet.discharge(requested_kwh, ServiceBand::Full) / timestep_h;
+        } else if grid_kw < -enforced_limit_kw {
+            let requested_kwh = (-enforced_limit_kw - grid_kw) * timestep_h;
+            transformer_relief_kw = -fleet.charge(requested_kwh, ServiceBand::Full) / timestep_h;
+        }
+        grid_kw -= transformer_relief_kw;
+        fleet.take_ramp_shortfall_kwh(); // what relief does not get shows up as residual excess
+
+        if i >= day_end {
+            let day = tick.timestamp.date_naive();
+            day_start = i;
+            day_end = i + ticks[i..]
+                .iter()
+                .take_while(|t| t.timestamp.date_naive() == day)
+                .count();
         }
-        // Charge for SRL_neg
-        else if tick.srl_neg_kwh < 0.0 {
-            // charge to fulfill SRL_neg
-            srl_neg_count += 1;
 
-            let requested = -tick.srl_neg_kwh;
-            let possible = max_charge_kwh.min(e_max);
-            let fulfilled = requested.min(possible);
+        // The strategy decides what each service gets, the engine serves it within the limits
+        let battery = fleet.state();
+        let ctx = DispatchContext {
+            config,
+            history: &ticks[..i],
+            timestep_h,
+            load_kw: tick.power_kw - transformer_relief_kw,
+            aux_grid_kw: fleet.aux_grid_kw(),
+            spot_prices_today: &spot_prices[day_start..day_end],
+            tick_of_day: i - day_start,
+        };
+        let setpoints = strategy.dispatch(tick, &battery, &ctx);
 
-            srl_energy_in_kwh = fulfilled;
-            soc_kwh += fulfilled * eff;
+        let tick_month = (tick.timestamp.year(), tick.timestamp.month());
+        if month != Some(tick_month) {
+            month = Some(tick_month);
+            month_peak_kw = 0.0;
         }
 
-        // Revenue from SRL
-        let revenue_pos = tick.srl_pos_price_eur_mwh / 1000.0 * srl_energy_out_kwh;
-        let revenue_neg = -tick.srl_neg_price_eur_mwh / 1000.0 * srl_energy_in_kwh;
+        // SRL and peak shaving in the configured order; value-based, the more valuable
+        // service goes first where both compete for the same energy or power
+        let competition = Competition::detect(
+            tick,
+            &setpoints,
+            &battery,
+            grid_kw,
+            month_peak_kw,
+            config,
+            timestep_h,
+        );
+        let ps_first = config
+            .service_priority
+            .peak_shaving_first(competition.as_ref());
+        let order = if ps_first {
+            [Service::PeakShaving, Service::Srl]
+        } else {
+            [Service::Srl, Service::PeakShaving]
+        };
+
+        let mut srl_energy_in_kwh = 0.0;
+        let mut srl_energy_out_kwh = 0.0;
+        let mut srl_unmet_pos_kwh = 0.0;
+        let mut srl_unmet_neg_kwh = 0.0;
+        let mut srl_curtailed_kwh = 0.0;
+        let mut srl_ramp_shortfall_kwh = 0.0;
 
-        // peak shaving
         let mut battery_in_kw = 0.0;
         let mut battery_out_kw = 0.0;
+        let mut ps_unmet_out_kwh = 0.0;
+        let mut ps_unmet_in_kwh = 0.0;
+        let mut ps_curtailed_kwh = 0.0;
+        let mut ps_ramp_shortfall_kwh = 0.0;
+
+        for service in order {
+            match service {
+                Service::Srl => {
+                    // Discharge for SRL_pos
+                    if setpoints.srl_pos_kwh > 0.0 {
+                        srl_pos_count += 1;
+
+                        let requested = setpoints.srl_pos_kwh;
+                        let allowed = requested.min(export_headroom_kwh(grid_kw));
+                        srl_curtailed_kwh += requested - allowed;
+                        srl_energy_out_kwh = fleet.discharge(allowed, ServiceBand::Full);
+                        srl_unmet_pos_kwh = requested - srl_energy_out_kwh;
+                        grid_kw -= srl_energy_out_kwh / timestep_h;
+                    }
+                    // Charge for SRL_neg
+                    if setpoints.srl_neg_kwh > 0.0 {
+                        srl_neg_count += 1;
+
+                        let requested = setpoints.srl_neg_kwh;
+                        let allowed = requested.min(import_headroom_kwh(grid_kw));
+                        srl_curtailed_kwh += requested - allowed;
+                        srl_energy_in_kwh = fleet.charge(allowed, ServiceBand::Full);
+                        srl_unmet_neg_kwh = requested - srl_energy_in_kwh;
+                        grid_kw += srl_energy_in_kwh / timestep_h;
+                    }
+
+                    srl_ramp_shortfall_kwh = fleet.take_ramp_shortfall_kwh();
+                }
+                Service::PeakShaving => {
+                    if setpoints.ps_discharge_kwh > 0.0 {
+                        let requested_kwh = setpoints.ps_discharge_kwh;
+                        let allowed_kwh = requested_kwh.min(export_headroom_kwh(grid_kw));
+                        ps_curtailed_kwh += requested_kwh - allowed_kwh;
+                        let fulfilled_kwh =
+                            fleet.discharge(allowed_kwh, ServiceBand::OutsideReserve);
+
+                        battery_out_kw = fulfilled_kwh / timestep_h;
+                        ps_unmet_out_kwh = requested_kwh - fulfilled_kwh;
+                        grid_kw -= battery_out_kw;
+                    }
+                    if setpoints.ps_charge_kwh > 0.0 {
+                        let requested_kwh = setpoints.ps_charge_kwh;
+                        let allowed_kwh = requested_kwh.min(import_headroom_kwh(grid_kw));
+                        ps_curtailed_kwh += requested_kwh - allowed_kwh;
+                        let fulfilled_kwh = fleet.charge(allowed_kwh, ServiceBand::OutsideReserve);
+
+                        battery_in_kw = fulfilled_kwh / timestep_h;
+                        ps_unmet_in_kwh = requested_kwh - fulfilled_kwh;
+                        grid_kw += battery_in_kw;
+                    }
+
+                    ps_ramp_shortfall_kwh = fleet.take_ramp_shortfall_kwh();
+                }
+            }
+        }
 
-        let threshold = config.ps_threshold_kw;
-        let epsilon = 1e-6;
+        // What the service served second lost to the first where they competed
+        let opportunity_cost_chf = competition.map_or(0.0, |c| {
+            let (srl_unmet_kwh, srl_kwh, ps_unmet_kwh, ps_kwh) = if c.discharge {
+                let ps_kwh = battery_out_kw * timestep_h;
+                (
+                    srl_unmet_pos_kwh,
+                    srl_energy_out_kwh,
+                    ps_unmet_out_kwh,
+                    ps_kwh,
+                )
+            } else {
+                let ps_kwh = battery_in_kw * timestep_h;
+                (
+                    srl_unmet_neg_kwh,
+                    srl_energy_in_kwh,
+                    ps_unmet_in_kwh,
+                    ps_kwh,
+                )
+            };
+            if ps_first {
+                srl_unmet_kwh.min(ps_kwh) * c.srl_chf_per_kwh
+            } else {
+                ps_unmet_kwh.min(srl_kwh).min(c.ps_contested_kwh) * c.ps_chf_per_kwh
+            }
+        });
 
-        // Discharge only if power > +threshold
-        if tick.power_kw > threshold + epsilon {
-            let requested_kwh = (tick.power_kw - threshold).min(p_max) * timestep_h;
-            let fulfilled_kwh = requested_kwh.min(max_discharge_kwh);
+        // Revenue from SRL
+        let revenue_pos = tick.srl_pos_price_eur_mwh / 1000.0 * srl_energy_out_kwh;
+        let revenue_neg = -tick.srl_neg_price_eur_mwh / 1000.0 * srl_energy_in_kwh;
 
-            battery_out_kw = fulfilled_kwh / timestep_h;
-            soc_kwh -= fulfilled_kwh;
+        // Time-of-use shifting next, outside the SRL reserve
+        let mut tou_out_kwh = 0.0;
+        let mut tou_in_kwh = 0.0;
+        let mut tou_curtailed_kwh = 0.0;
+
+        if setpoints.tou_discharge_kwh > 0.0 {
+            let requested_kwh = setpoints.tou_discharge_kwh;
+            let allowed_kwh = requested_kwh.min(export_headroom_kwh(grid_kw));
+            tou_curtailed_kwh += requested_kwh - allowed_kwh;
+            tou_out_kwh = fleet.discharge(allowed_kwh, ServiceBand::OutsideReserve);
+            grid_kw -= tou_out_kwh / timestep_h;
         }
-
-        // Charge only if power < -threshold
-        else if tick.power_kw < -threshold - epsilon {
-            let requested_kwh = (-threshold - tick.power_kw).min(p_max) * timestep_h;
-            let fulfilled_kwh = requested_kwh.min(max_charge_kwh);
-
-            battery_in_kw = fulfilled_kwh / timestep_h;
-            soc_kwh += fulfilled_kwh;
+        if setpoints.tou_charge_kwh > 0.0 {
+            let requested_kwh = setpoints.tou_charge_kwh;
+            let allowed_kwh = requested_kwh.min(import_headroom_kwh(grid_kw));
+            tou_curtailed_kwh += requested_kwh - allowed_kwh;
+            tou_in_kwh = fleet.charge(allowed_kwh, ServiceBand::OutsideReserve);
+            grid_kw += tou_in_kwh / timestep_h;
+        }
+        fleet.take_ramp_shortfall_kwh(); // no delivery obligation either
+
+        // Arbitrage uses what the services before it left, outside the SRL reserve
+        let mut arbitrage_out_kwh = 0.0;
+        let mut arbitrage_in_kwh = 0.0;
+        let mut arbitrage_curtailed_kwh = 0.0;
+
+        if setpoints.arbitrage_discharge_kwh > 0.0 {
+            let requested_kwh = setpoints.arbitrage_discharge_kwh;
+            let allowed_kwh = requested_kwh.min(export_headroom_kwh(grid_kw));
+            arbitrage_curtailed_kwh += requested_kwh - allowed_kwh;
+            arbitrage_out_kwh = fleet.discharge(allowed_kwh, ServiceBand::OutsideReserve);
+            grid_kw -= arbitrage_out_kwh / timestep_h;
+        }
+        if setpoints.arbitrage_charge_kwh > 0.0 {
+            let requested_kwh = setpoints.arbitrage_charge_kwh;
+            let allowed_kwh = requested_kwh.min(import_headroom_kwh(grid_kw));
+            arbitrage_curtailed_kwh += requested_kwh - allowed_kwh;
+            arbitrage_in_kwh = fleet.charge(
+                allowed_kwh.min(threshold_headroom_kwh(grid_kw)),
+                ServiceBand::OutsideReserve,
+            );
+            grid_kw += arbitrage_in_kwh / timestep_h;
+        }
+        let arbitrage_unmet_kwh = setpoints.arbitrage_discharge_kwh
+            + setpoints.arbitrage_charge_kwh
+            - arbitrage_out_kwh
+            - arbitrage_in_kwh;
+        fleet.take_ramp_shortfall_kwh(); // arbitrage has no delivery obligation
+
+        let arbitrage_revenue_chf = tick.spot_price_eur_mwh.unwrap_or(0.0) / 1000.0
+            * config.arbitrage.chf_per_eur
+            * (arbitrage_out_kwh - arbitrage_in_kwh);
+
+        // PV export above the feed-in limit: the battery absorbs first, the rest is curtailed.
+        // Only what the site's PV pushes over the limit, not what battery services export.
+        let mut pv_absorbed_kwh = 0.0;
+        let mut pv_curtailed_kwh = 0.0;
+        if let Some(pv_kw) = tick.pv_kw {
+            let excess_kwh = (-grid_kw - feed_in_limit_kw)
+                .min(-tick.power_kw - feed_in_limit_kw)
+                .min(pv_kw)
+                * timestep_h;
+            if excess_kwh > 0.0 {
+                pv_absorbed_kwh = fleet.charge(excess_kwh, ServiceBand::OutsideReserve);
+                pv_curtailed_kwh = excess_kwh - pv_absorbed_kwh;
+            }
+        }
+        fleet.take_ramp_shortfall_kwh(); // whatever ramping keeps from the battery is curtailed
+
+        // Idle ticks: move the SoC toward the target band with what grid and threshold leave
+        let mut recovery_in_kwh = 0.0;
+        let mut recovery_out_kwh = 0.0;
+        if config.soc_recovery.enabled
+            && setpoints.is_idle()
+            && transformer_relief_kw == 0.0
+            && pv_absorbed_kwh == 0.0
+        {
+            let high_tariff = config
+                .tariff
+                .as_ref()
+                .map(|t| t.is_high_tariff(tick.timestamp));
+            let requested_kwh = config.soc_recovery.requested_kwh(
+                &fleet.state(),
+                efficiency,
+                timestep_h,
+                high_tariff,
+            );
+
+            if requested_kwh > 0.0 {
+                let allowed_kwh = requested_kwh
+                    .min(import_headroom_kwh(grid_kw))
+                    .min(threshold_headroom_kwh(grid_kw));
+                recovery_in_kwh = fleet.charge(allowed_kwh, ServiceBand::Full);
+            } else if requested_kwh < 0.0 {
+                // Only covers site load, never exports
+                let allowed_kwh = (-requested_kwh).min(grid_kw.max(0.0) * timestep_h);
+                recovery_out_kwh = fleet.discharge(allowed_kwh, ServiceBand::Full);
+            }
+            fleet.take_ramp_shortfall_kwh(); // no obligation, next idle tick continues
         }
 
+        // Book every unit, then aggregate the fleet
+        let unit_ticks: Vec<UnitTickResult> = units
+            .iter_mut()
+            .zip(fleet.units)
+            .map(|(unit, unit_tick)| {
+                unit.finish_tick(unit_tick, tick.timestamp, tick.ambient_temp_c, timestep_h)
+            })
+            .collect();
+        let sum = |f: fn(&UnitTickResult) -> f64| unit_ticks.iter().map(f).sum::<f64>();
+
+        let battery_net_kw = sum(|u| u.battery_net_kw);
+        let ramp_hold_kw = sum(|u| u.ramp_hold_kw);
+        let soc_kwh = sum(|u| u.soc_kwh);
+        let soc_min_kwh = sum(|u| u.soc_min_kwh);
+        let soc_max_kwh = sum(|u| u.soc_max_kwh);
+        let soc_percent = 100.0 * (soc_kwh - soc_min_kwh) / (soc_max_kwh - soc_min_kwh);
+        let aux_load_kw = sum(|u| u.aux_load_kw);
+        let aux_battery_kw = sum(|u| u.aux_battery_kw);
+
+        // SoH weighted by nameplate, temperature of the hottest unit
+        let soh = unit_ticks
+            .iter()
+            .zip(&capacities_kwh)
+            .map(|(u, c)| u.soh * c)
+            .sum::<f64>()
+            / nameplate_kwh;
+        let resistance_factor = unit_ticks
+            .iter()
+            .zip(&capacities_kwh)
+            .map(|(u, c)| u.resistance_factor * c)
+            .sum::<f64>()
+            / nameplate_kwh;
+        let cell_temp_c = unit_ticks
+            .iter()
+            .map(|u| u.cell_temp_c)
+            .fold(f64::MIN, f64::max);
+
+        // Pack voltage / current only make sense for a single unit
+        let (pack_voltage_v, pack_current_a) = match unit_ticks.as_slice() {
+            [only] => (only.pack_voltage_v, only.pack_current_a),
+            _ => (None, None),
+        };
 
-        // SoC and Output
-        soc_kwh = soc_kwh.clamp(soc_min, soc_max);
-        let soc_percent = 100.0 * (soc_kwh - soc_min) / (soc_max - soc_min);
-
-        let grid_net_kw = tick.power_kw + battery_in_kw - battery_out_kw;
-        let transformer_violation = grid_net_kw.abs() > config.transformer_limit_kw;
+        // The same grid power the services were limited by, SRL included
+        let grid_net_kw = tick.power_kw + battery_in_kw - battery_out_kw
+            + (srl_energy_in_kwh - srl_energy_out_kwh) / timestep_h
+            + (tou_in_kwh - tou_out_kwh) / timestep_h
+            + (arbitrage_in_kwh - arbitrage_out_kwh) / timestep_h
+            + (pv_absorbed_kwh + pv_curtailed_kwh) / timestep_h
+            + (recovery_in_kwh - recovery_out_kwh) / timestep_h
+            + aux_load_kw
+            - aux_battery_kw
+            - ramp_hold_kw
+            - transformer_relief_kw;
+        month_peak_kw = month_peak_kw.max(grid_net_kw);
+        // Reactive compensation with what the inverters have left next to their active power
+        let reactive_capability_kvar = if config.reactive_compensation {
+            unit_ticks
+                .iter()
+                .map(|u| {
+                    (u.inverter_limit_kw.powi(2) - u.battery_net_kw.powi(2))
+                        .max(0.0)
+                        .sqrt()
+                })
+                .sum()
+        } else {
+            0.0
+        };
+        let bess_reactive_kvar =
+            site_reactive_kvar.clamp(-reactive_capability_kvar, reactive_capability_kvar);
+        let grid_reactive_kvar = site_reactive_kvar - bess_reactive_kvar;
+        let grid_apparent_kva = grid_net_kw.hypot(grid_reactive_kvar);
+
+        let transformer_excess_kva = (grid_apparent_kva - config.transformer_limit_kva).max(0.0);
+        let transformer_violation = transformer_excess_kva > 0.0;
+        let transformer_temps = transformer_thermal.update(
+            grid_apparent_kva / config.transformer_limit_kva,
+            tick.ambient_temp_c,
+            timestep_h,
+        );
 
         // Output Result
         let result = SimulationTickResult {
             timestamp: tick.timestamp,
 
             original_power_kw: tick.power_kw,
+            pv_kw: tick.pv_kw,
+            pv_absorbed_kwh,
+            pv_curtailed_kwh,
             srl_pos_kwh: tick.srl_pos_kwh,
             srl_neg_kwh: tick.srl_neg_kwh,
 
@@ -108,24 +461,85 @@ pub fn run_simulation(
             srl_energy_in_kwh,
             srl_energy_out_kwh,
 
+            tou_out_kwh,
+            tou_in_kwh,
+
+            spot_price_eur_mwh: tick.spot_price_eur_mwh,
+            arbitrage_out_kwh,
+            arbitrage_in_kwh,
+            arbitrage_unmet_kwh,
+            arbitrage_revenue_chf,
+
+            recovery_in_kwh,
+            recovery_out_kwh,
+
+            srl_unmet_pos_kwh,
+            srl_unmet_neg_kwh,
+            ps_unmet_out_kwh,
+            ps_unmet_in_kwh,
+            srl_ramp_shortfall_kwh,
+            ps_ramp_shortfall_kwh,
+
+            battery_net_kw,
+            ramp_hold_kw,
+
             soc_kwh,
             soc_percent,
 
+            self_discharge_kwh: sum(|u| u.self_discharge_kwh),
+            aux_load_kw,
+            aux_battery_kw,
+
+            cell_temp_c,
+            hvac_kw: sum(|u| u.hvac_kw),
+
+            battery_charge_limit_kw: sum(|u| u.battery_charge_limit_kw),
+            battery_discharge_limit_kw: sum(|u| u.battery_discharge_limit_kw),
+            inverter_limit_kw: sum(|u| u.inverter_limit_kw),
+            p_charge_limit_kw: sum(|u| u.p_charge_limit_kw),
+            p_discharge_limit_kw: sum(|u| u.p_discharge_limit_kw),
+
+            pack_voltage_v,
+            pack_current_a,
+
+            soh,
+            resistance_factor,
+
             original_grid_kw: tick.power_kw,
             final_grid_kw: grid_net_kw,
 
             grid_net_kw,
+            site_reactive_kvar,
+            bess_reactive_kvar,
+            grid_reactive_kvar,
+            grid_apparent_kva,
             transformer_violation,
+            transformer_excess_kva,
+            transformer_top_oil_c: transformer_temps.as_ref().map(|t| t.top_oil_c),
+            transformer_hot_spot_c: transformer_temps.as_ref().map(|t| t.hot_spot_c),
+            transformer_aging_rate: transformer_temps.as_ref().map(|t| t.aging_rate),
+            transformer_relief_kw,
+            srl_curtailed_kwh,
+            ps_curtailed_kwh,
+            tou_curtailed_kwh,
+            arbitrage_curtailed_kwh,
 
             srl_revenue_pos_chf: revenue_pos,
             srl_revenue_neg_chf: revenue_neg,
+
+            services_competing: competition.is_some(),
+            opportunity_cost_chf,
         };
 
         results.push(result);
+        unit_results.extend(unit_ticks);
     }
 
     println!("SRL pos ticks: {}", srl_pos_count);
     println!("SRL neg ticks: {}", srl_neg_count);
 
-    results
+    SimulationRun {
+        ticks: results,
+        units: unit_results,
+    }
 }
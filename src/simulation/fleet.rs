@@ -0,0 +1,240 @@
+use super::strategy::BatteryState;
+use super::unit::{ServiceBand, UnitTick};
+use serde::{Deserialize, Serialize};
+
+/// How a service request is split across the units of the fleet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Allocator {
+    /// Proportional to nameplate capacity.
+    ProportionalToCapacity,
+    /// Proportional to the energy each unit can still give (discharge) or take (charge),
+    /// so fuller units discharge more and emptier units charge more.
+    SocBalancing,
+    /// Fill units in the order they are configured.
+    Priority,
+}
+
+/// Fleet-wide view of the per-unit budgets of one tick.
+///
+/// Services draw from the fleet as if it were one battery; every draw is split
+/// across the units by the allocator, each unit enforcing its own limits.
+pub struct FleetBudget {
+    pub units: Vec<UnitTick>,
+    capacities_kwh: Vec<f64>,
+    allocator: Allocator,
+    ramp_shortfall_kwh: f64, // not yet taken by the current service
+}
+
+impl FleetBudget {
+    pub fn new(units: Vec<UnitTick>, capacities_kwh: Vec<f64>, allocator: Allocator) -> Self {
+        Self {
+            units,
+            capacities_kwh,
+            allocator,
+            ramp_shortfall_kwh: 0.0,
+        }
+    }
+
+    /// Auxiliary load the units draw from the grid this tick.
+    pub fn aux_grid_kw(&self) -> f64 {
+        self.units.iter().map(|u| u.aux_grid_kw()).sum()
+    }
+
+    /// Aggregate state of the fleet for the dispatch strategy.
+    pub fn state(&self) -> BatteryState {
+        let sum = |f: fn(&UnitTick) -> f64| self.units.iter().map(f).sum::<f64>();
+        let soc_min_kwh = sum(|u| u.floor_kwh(ServiceBand::Full));
+        let soc_max_kwh = sum(|u| u.ceiling_kwh(ServiceBand::Full));
+
+        BatteryState {
+            soc_kwh: sum(|u| u.budget.soc_kwh()),
+            soc_min_kwh,
+            soc_max_kwh,
+            reserve_pos_kwh: sum(|u| u.floor_kwh(ServiceBand::OutsideReserve)) - soc_min_kwh,
+            reserve_neg_kwh: soc_max_kwh - sum(|u| u.ceiling_kwh(ServiceBand::OutsideReserve)),
+            max_discharge_kwh: sum(|u| {
+                u.budget
+                    .available_discharge_kwh(u.floor_kwh(ServiceBand::Full))
+            }),
+            max_charge_kwh: sum(|u| {
+                u.budget
+                    .available_charge_kwh(u.ceiling_kwh(ServiceBand::Full))
+            }),
+            outside_reserve_discharge_kwh: sum(|u| {
+                u.budget
+                    .available_discharge_kwh(u.floor_kwh(ServiceBand::OutsideReserve))
+            }),
+            outside_reserve_charge_kwh: sum(|u| {
+                u.budget
+                    .available_charge_kwh(u.ceiling_kwh(ServiceBand::OutsideReserve))
+            }),
+        }
+    }
+
+    /// Discharges up to `requested_kwh` (grid side) across the fleet, returns the delivered energy.
+    pub fn discharge(&mut self, requested_kwh: f64, band: ServiceBand) -> f64 {
+        let requested_kwh = requested_kwh.max(0.0);
+        let available: Vec<f64> = self
+            .units
+            .iter()
+            .map(|u| u.budget.available_discharge_kwh(u.floor_kwh(band)))
+            .collect();
+        let unramped: f64 = self
+            .units
+            .iter()
+            .map(|u| u.budget.unramped_discharge_kwh(u.floor_kwh(band)))
+            .sum();
+        let headroom: Vec<f64> = self
+            .units
+            .iter()
+            .map(|u| (u.budget.soc_kwh() - u.floor_kwh(band)).max(0.0))
+            .collect();
+
+        let shares = self.split(requested_kwh, &available, &headroom);
+        let mut fulfilled = 0.0;
+        for (unit, share) in self.units.iter_mut().zip(shares) {
+            let floor = unit.floor_kwh(band);
+            fulfilled += unit.budget.discharge(share, floor);
+        }
+
+        self.ramp_shortfall_kwh += (requested_kwh.min(unramped) - fulfilled).max(0.0);
+        fulfilled
+    }
+
+    /// Charges up to `requested_kwh` (grid side) across the fleet, returns the absorbed energy.
+    pub fn charge(&mut self, requested_kwh: f64, band: ServiceBand) -> f64 {
+        let requested_kwh = requested_kwh.max(0.0);
+        let available: Vec<f64> = self
+            .units
+            .iter()
+            .map(|u| u.budget.available_charge_kwh(u.ceiling_kwh(band)))
+            .collect();
+        let unramped: f64 = self
+            .units
+            .iter()
+            .map(|u| u.budget.unramped_charge_kwh(u.ceiling_kwh(band)))
+            .sum();
+        let headroom: Vec<f64> = self
+            .units
+            .iter()
+            .map(|u| (u.ceiling_kwh(band) - u.budget.soc_kwh()).max(0.0))
+            .collect();
+
+        let shares = self.split(requested_kwh, &available, &headroom);
+        let mut fulfilled = 0.0;
+        for (unit, share) in self.units.iter_mut().zip(shares) {
+            let ceiling = unit.ceiling_kwh(band);
+            fulfilled += unit.budget.charge(share, ceiling);
+        }
+
+        self.ramp_shortfall_kwh += (requested_kwh.min(unramped) - fulfilled).max(0.0);
+        fulfilled
+    }
+
+    /// Part of the unmet energy since the last call that only ramp limits caused.
+    pub fn take_ramp_shortfall_kwh(&mut self) -> f64 {
+        std::mem::take(&mut self.ramp_shortfall_kwh)
+    }
+
+    fn split(&self, requested_kwh: f64, available: &[f64], headroom: &[f64]) -> Vec<f64> {
+        match self.allocator {
+            Allocator::ProportionalToCapacity => {
+                water_fill(requested_kwh, available, &self.capacities_kwh)
+            }
+            Allocator::SocBalancing => water_fill(requested_kwh, available, headroom),
+            Allocator::Priority => {
+                let mut remaining = requested_kwh;
+                available
+                    .iter()
+                    .map(|&a| {
+                        let share = remaining.min(a);
+                        remaining -= share;
+                        share
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Splits `requested` proportionally to `weights`, never above `available` per unit.
+/// What a saturated unit cannot take is redistributed over the others.
+fn water_fill(requested: f64, available: &[f64], weights: &[f64]) -> Vec<f64> {
+    let epsilon = 1e-12;
+    let mut shares = vec![0.0; available.len()];
+    let mut remaining = requested;
+    let mut open: Vec<usize> = (0..available.len())
+        .filter(|&i| available[i] > epsilon)
+        .collect();
+
+    while remaining > epsilon && !open.is_empty() {
+        let weight_sum: f64 = open.iter().map(|&i| weights[i].max(0.0)).sum();
+        let weight = |i: usize| {
+            if weight_sum > epsilon {
+                weights[i].max(0.0) / weight_sum
+            } else {
+                1.0 / open.len() as f64
+            }
+        };
+
+        let mut given = 0.0;
+        for &i in &open {
+            let share = (remaining * weight(i)).min(available[i] - shares[i]);
+            shares[i] += share;
+            given += share;
+        }
+        remaining -= given;
+
+        open.retain(|&i| available[i] - shares[i] > epsilon);
+        if given <= epsilon {
+            break;
+        }
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn water_fill_splits_by_weight() {
+        let shares = water_fill(30.0, &[100.0, 100.0], &[1.0, 2.0]);
+        assert_close(shares[0], 10.0);
+        assert_close(shares[1], 20.0);
+    }
+
+    #[test]
+    fn water_fill_redistributes_above_caps() {
+        let available = [5.0, 100.0, 20.0];
+        let shares = water_fill(60.0, &available, &[1.0, 1.0, 1.0]);
+
+        assert_close(shares.iter().sum(), 60.0);
+        assert_close(shares[0], 5.0);
+        assert_close(shares[2], 20.0);
+        for (share, cap) in shares.iter().zip(available) {
+            assert!(*share <= cap + 1e-9);
+        }
+    }
+
+    #[test]
+    fn water_fill_stops_at_total_availability() {
+        let shares = water_fill(500.0, &[10.0, 0.0, 30.0], &[3.0, 1.0, 0.0]);
+        assert_close(shares[0], 10.0);
+        assert_close(shares[1], 0.0);
+        assert_close(shares[2], 30.0);
+    }
+
+    #[test]
+    fn water_fill_without_weights_splits_evenly() {
+        let shares = water_fill(9.0, &[10.0, 10.0, 10.0], &[0.0, 0.0, 0.0]);
+        for share in shares {
+            assert_close(share, 3.0);
+        }
+    }
+}
@@ -0,0 +1,461 @@
+use crate::model::mergedseries::MergedTick;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+/// How a series is forecast; every method only looks at past values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ForecastMethod {
+    Persistence,                                      // last known value
+    SeasonalNaive { days: usize },                    // same time `days` ago, 7 = last week
+    ExponentialSmoothing { alpha: f64, days: usize }, // same time of day, smoothed over `days`
+    Quantile { quantile: f64, days: usize },          // of the same time of day over `days`
+}
+
+impl ForecastMethod {
+    /// Past ticks the method needs for a horizon of up to one day.
+    pub fn lookback_ticks(&self, ticks_per_day: usize) -> usize {
+        match self {
+            ForecastMethod::Persistence => 1,
+            ForecastMethod::SeasonalNaive { days } => days.max(&1) * ticks_per_day,
+            ForecastMethod::ExponentialSmoothing { days, .. }
+            | ForecastMethod::Quantile { days, .. } => (days + 1) * ticks_per_day,
+        }
+    }
+
+    /// Forecast of the `horizon` values following `past`.
+    pub fn forecast(&self, past: &[f64], horizon: usize, ticks_per_day: usize) -> Vec<f64> {
+        let now = past.len();
+        let last = past.last().copied().unwrap_or(0.0);
+
+        // Same time of day on up to `days` earlier days, newest first, never the future
+        let same_time = |k: usize, days: usize| {
+            (1..)
+                .map_while(|d| (now + k).checked_sub(d * ticks_per_day))
+                .filter(|&j| j < now)
+                .take(days)
+                .map(|j| past[j])
+                .collect::<Vec<f64>>()
+        };
+
+        (0..horizon)
+            .map(|k| match self {
+                ForecastMethod::Persistence => last,
+                ForecastMethod::SeasonalNaive { days } => {
+                    let period = days.max(&1) * ticks_per_day;
+                    (now + k % period)
+                        .checked_sub(period)
+                        .map_or(last, |j| past[j])
+                }
+                ForecastMethod::ExponentialSmoothing { alpha, days } => {
+                    let samples = same_time(k, *days);
+                    match samples.split_last() {
+                        Some((oldest, newer)) => newer
+                            .iter()
+                            .rev()
+                            .fold(*oldest, |level, x| alpha * x + (1.0 - alpha) * level),
+                        None => last,
+                    }
+                }
+                ForecastMethod::Quantile { quantile, days } => {
+                    let mut samples = same_time(k, *days);
+                    if samples.is_empty() {
+                        return last;
+                    }
+                    samples.sort_by(f64::total_cmp);
+                    let rank = quantile.clamp(0.0, 1.0) * (samples.len() - 1) as f64;
+                    let (lower, upper) = (
+                        samples[rank.floor() as usize],
+                        samples[rank.ceil() as usize],
+                    );
+                    lower + (upper - lower) * rank.fract()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Series a forecast is made for.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum ForecastTarget {
+    Load,             // gross consumption of the site, PV added back
+    Pv,               // generation
+    SrlPosActivation, // 1 when positive SRL is activated, forecasts are probabilities
+    SrlNegActivation,
+}
+
+impl ForecastTarget {
+    pub fn value(self, tick: &MergedTick) -> f64 {
+        let activated = |energy_kwh: f64| if energy_kwh != 0.0 { 1.0 } else { 0.0 };
+        match self {
+            ForecastTarget::Load => tick.power_kw + tick.pv_kw.unwrap_or(0.0),
+            ForecastTarget::Pv => tick.pv_kw.unwrap_or(0.0),
+            ForecastTarget::SrlPosActivation => activated(tick.srl_pos_kwh.max(0.0)),
+            ForecastTarget::SrlNegActivation => activated(tick.srl_neg_kwh.min(0.0)),
+        }
+    }
+}
+
+/// Methods for the series a forecast-based strategy needs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastConfig {
+    pub load: ForecastMethod,
+    pub pv: ForecastMethod,
+    pub srl_activation: ForecastMethod, // positive and negative probability alike
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            load: ForecastMethod::SeasonalNaive { days: 7 },
+            pv: ForecastMethod::SeasonalNaive { days: 1 },
+            srl_activation: ForecastMethod::ExponentialSmoothing {
+                alpha: 0.2,
+                days: 28,
+            },
+        }
+    }
+}
+
+/// Forecast ticks for a planning horizon: `horizon` ticks starting with `current`.
+///
+/// The current tick is measured and taken as is. Net load is the load forecast minus the
+/// PV forecast, SRL activation the forecast probability times the mean activation energy
+/// of the lookback window. Prices and the remaining fields repeat the previous day.
+pub fn forecast_ticks(
+    history: &[MergedTick],
+    current: &MergedTick,
+    horizon: usize,
+    config: &ForecastConfig,
+    timestep_minutes: f64,
+) -> Vec<MergedTick> {
+    let ticks_per_day = ((1440.0 / timestep_minutes).round() as usize).max(1);
+    let mut ticks = seasonal_naive(history, current, horizon, ticks_per_day, timestep_minutes);
+    if horizon <= 1 {
+        return ticks;
+    }
+
+    // Past values including the current tick, only as far back as the method looks
+    let series = |method: &ForecastMethod, target: ForecastTarget| {
+        let lookback = method.lookback_ticks(ticks_per_day);
+        let start = history.len().saturating_sub(lookback);
+        let past: Vec<f64> = history[start..]
+            .iter()
+            .chain(std::iter::once(current))
+            .map(|t| target.value(t))
+            .collect();
+        method.forecast(&past, horizon - 1, ticks_per_day)
+    };
+
+    let load = series(&config.load, ForecastTarget::Load);
+    let pv = series(&config.pv, ForecastTarget::Pv);
+    let srl_pos = series(&config.srl_activation, ForecastTarget::SrlPosActivation);
+    let srl_neg = series(&config.srl_activation, ForecastTarget::SrlNegActivation);
+
+    // Mean energy of an activation
+    let window = &history[history
+        .len()
+        .saturating_sub(config.srl_activation.lookback_ticks(ticks_per_day))..];
+    let mean = |energies: Vec<f64>| {
+        if energies.is_empty() {
+            0.0
+        } else {
+            energies.iter().sum::<f64>() / energies.len() as f64
+        }
+    };
+    let pos_kwh = mean(
+        window
+            .iter()
+            .map(|t| t.srl_pos_kwh)
+            .filter(|e| *e > 0.0)
+            .collect(),
+    );
+    let neg_kwh = mean(
+        window
+            .iter()
+            .map(|t| t.srl_neg_kwh)
+            .filter(|e| *e < 0.0)
+            .collect(),
+    );
+
+    for (k, tick) in ticks.iter_mut().enumerate().skip(1) {
+        tick.power_kw = load[k - 1] - pv[k - 1];
+        tick.pv_kw = current.pv_kw.map(|_| pv[k - 1]);
+        tick.srl_pos_kwh = srl_pos[k - 1] * pos_kwh;
+        tick.srl_neg_kwh = srl_neg[k - 1] * neg_kwh;
+    }
+    ticks
+}
+
+/// Seasonal-naive forecast: each future tick repeats the one a `period` earlier.
+///
+/// Returns `horizon` ticks starting with `current`, which is measured and taken as is.
+/// Only `history` and `current` are used; where a full period is missing, the current
+/// tick is repeated (persistence). Timestamps continue from `current`.
+pub fn seasonal_naive(
+    history: &[MergedTick],
+    current: &MergedTick,
+    horizon: usize,
+    period: usize,
+    timestep_minutes: f64,
+) -> Vec<MergedTick> {
+    let now = history.len();
+    let step = Duration::milliseconds((timestep_minutes * 60_000.0) as i64);
+
+    (0..horizon)
+        .map(|k| {
+            // Latest tick at the same position in an earlier period, never in the future
+            let periods_back = k.div_ceil(period.max(1));
+            let source = (now + k)
+                .checked_sub(periods_back * period)
+                .filter(|&j| j < now)
+                .map_or(current, |j| &history[j]);
+
+            MergedTick {
+                timestamp: current.timestamp + step * k as i32,
+                ..source.clone()
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastEvaluationConfig {
+    pub enabled: bool,
+    pub horizon_hours: f64,      // forecast length from each origin
+    pub origin_every_hours: f64, // how often a forecast is issued
+    pub methods: Vec<ForecastMethod>,
+}
+
+impl Default for ForecastEvaluationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            horizon_hours: 24.0,
+            origin_every_hours: 24.0,
+            methods: vec![
+                ForecastMethod::Persistence,
+                ForecastMethod::SeasonalNaive { days: 1 },
+                ForecastMethod::SeasonalNaive { days: 7 },
+                ForecastMethod::ExponentialSmoothing {
+                    alpha: 0.3,
+                    days: 14,
+                },
+                ForecastMethod::Quantile {
+                    quantile: 0.5,
+                    days: 14,
+                },
+            ],
+        }
+    }
+}
+
+/// Accuracy of one method on one target over the historical series.
+#[derive(Debug, Serialize)]
+pub struct ForecastAccuracy {
+    pub target: String,
+    pub method: String,
+    pub forecasts: usize, // origins evaluated
+    pub mae: f64,
+    pub rmse: f64,
+    pub peak_timing_error_minutes: Option<f64>, // mean, load and PV only
+}
+
+/// Replays the series as if forecasting live: from each origin after the first day, every
+/// method forecasts the next `horizon_hours` from the ticks before the origin only.
+pub fn evaluate_forecasts(
+    ticks: &[MergedTick],
+    config: &ForecastEvaluationConfig,
+    timestep_minutes: f64,
+) -> Vec<ForecastAccuracy> {
+    let ticks_per_day = ((1440.0 / timestep_minutes).round() as usize).max(1);
+    let horizon = ((config.horizon_hours * 60.0 / timestep_minutes).round() as usize).max(1);
+    let every = ((config.origin_every_hours * 60.0 / timestep_minutes).round() as usize).max(1);
+
+    let mut targets = vec![
+        ForecastTarget::Load,
+        ForecastTarget::SrlPosActivation,
+        ForecastTarget::SrlNegActivation,
+    ];
+    if ticks.iter().any(|t| t.pv_kw.is_some()) {
+        targets.insert(1, ForecastTarget::Pv);
+    }
+
+    let mut accuracy = Vec::new();
+    for target in targets {
+        let values: Vec<f64> = ticks.iter().map(|t| target.value(t)).collect();
+        let peak_timing = matches!(target, ForecastTarget::Load | ForecastTarget::Pv);
+
+        for method in &config.methods {
+            let mut forecasts = 0;
+            let mut abs_sum = 0.0;
+            let mut sq_sum = 0.0;
+            let mut points = 0;
+            let mut timing_sum = 0.0;
+
+            let mut origin = ticks_per_day;
+            while origin < values.len() {
+                let actual = &values[origin..(origin + horizon).min(values.len())];
+                let start = origin.saturating_sub(method.lookback_ticks(ticks_per_day));
+                let predicted =
+                    method.forecast(&values[start..origin], actual.len(), ticks_per_day);
+
+                for (f, a) in predicted.iter().zip(actual) {
+                    abs_sum += (f - a).abs();
+                    sq_sum += (f - a).powi(2);
+                }
+                points += actual.len();
+                forecasts += 1;
+
+                let argmax = |v: &[f64]| {
+                    v.iter()
+                        .enumerate()
+                        .max_by(|a, b| a.1.total_cmp(b.1))
+                        .map_or(0, |(i, _)| i)
+                };
+                timing_sum += argmax(&predicted).abs_diff(argmax(actual)) as f64 * timestep_minutes;
+
+                origin += every;
+            }
+
+            let n = points.max(1) as f64;
+            accuracy.push(ForecastAccuracy {
+                target: format!("{:?}", target),
+                method: format!("{:?}", method),
+                forecasts,
+                mae: abs_sum / n,
+                rmse: (sq_sum / n).sqrt(),
+                peak_timing_error_minutes: peak_timing
+                    .then(|| timing_sum / forecasts.max(1) as f64),
+            });
+        }
+    }
+    accuracy
+}
+
+/// Prints the accuracy table of `evaluate_forecasts`.
+pub fn print_accuracy(accuracy: &[ForecastAccuracy]) {
+    println!("\n===== Forecast Accuracy =====\n");
+    for a in accuracy {
+        let timing = a
+            .peak_timing_error_minutes
+            .map_or("-".to_string(), |m| format!("{:.0} min", m));
+        println!(
+            "{:<17} {:<50} MAE {:>9.3}  RMSE {:>9.3}  peak timing {:>8}",
+            a.target, a.method, a.mae, a.rmse, timing
+        );
+    }
+    println!("=============================");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    /// Hourly ticks with the given load.
+    fn ticks(load_kw: &[f64]) -> Vec<MergedTick> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        load_kw
+            .iter()
+            .enumerate()
+            .map(|(i, &power_kw)| MergedTick {
+                timestamp: start + Duration::hours(i as i64),
+                power_kw,
+                srl_pos_kwh: 0.0,
+                srl_neg_kwh: 0.0,
+                srl_pos_price_eur_mwh: 0.0,
+                srl_neg_price_eur_mwh: 0.0,
+                ambient_temp_c: None,
+                reactive_kvar: None,
+                spot_price_eur_mwh: None,
+                pv_kw: None,
+            })
+            .collect()
+    }
+
+    /// Day-ahead accuracy of the load forecast with one method, hourly ticks.
+    fn load_accuracy(load_kw: &[f64], method: ForecastMethod) -> ForecastAccuracy {
+        let config = ForecastEvaluationConfig {
+            enabled: true,
+            horizon_hours: 24.0,
+            origin_every_hours: 24.0,
+            methods: vec![method],
+        };
+        evaluate_forecasts(&ticks(load_kw), &config, 60.0)
+            .into_iter()
+            .find(|a| a.target == "Load")
+            .unwrap()
+    }
+
+    fn day(peak_hour: usize, peak_kw: f64) -> Vec<f64> {
+        (0..24)
+            .map(|h| if h == peak_hour { peak_kw } else { 0.0 })
+            .collect()
+    }
+
+    #[test]
+    fn periodic_load_is_forecast_exactly() {
+        let load = [day(5, 10.0), day(5, 10.0), day(5, 10.0)].concat();
+        let accuracy = load_accuracy(&load, ForecastMethod::SeasonalNaive { days: 1 });
+
+        assert_eq!(accuracy.forecasts, 2);
+        assert_eq!(accuracy.mae, 0.0);
+        assert_eq!(accuracy.rmse, 0.0);
+        assert_eq!(accuracy.peak_timing_error_minutes, Some(0.0));
+    }
+
+    #[test]
+    fn shifted_peak_errors() {
+        // The forecast repeats day one: 10 kW missed at 08:00, 10 kW too much at 05:00
+        let load = [day(5, 10.0), day(8, 10.0)].concat();
+        let accuracy = load_accuracy(&load, ForecastMethod::SeasonalNaive { days: 1 });
+
+        assert_eq!(accuracy.forecasts, 1);
+        assert!((accuracy.mae - 20.0 / 24.0).abs() < 1e-12);
+        assert!((accuracy.rmse - (200.0f64 / 24.0).sqrt()).abs() < 1e-12);
+        assert_eq!(accuracy.peak_timing_error_minutes, Some(180.0));
+    }
+
+    #[test]
+    fn rmse_weighs_large_errors_more() {
+        // Persistence forecasts 0 for the whole day: one miss of 12 kW
+        let load = [vec![0.0; 24], day(3, 12.0)].concat();
+        let accuracy = load_accuracy(&load, ForecastMethod::Persistence);
+
+        assert!((accuracy.mae - 12.0 / 24.0).abs() < 1e-12);
+        assert!((accuracy.rmse - (144.0f64 / 24.0).sqrt()).abs() < 1e-12);
+        assert!(accuracy.rmse > accuracy.mae);
+    }
+
+    #[test]
+    fn methods_only_use_past_values() {
+        let past = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+        assert_eq!(
+            ForecastMethod::Persistence.forecast(&past, 2, 3),
+            [6.0, 6.0]
+        );
+        assert_eq!(
+            ForecastMethod::SeasonalNaive { days: 1 }.forecast(&past, 3, 3),
+            [4.0, 5.0, 6.0]
+        );
+        // Median of the same time on the last two days: 1 and 4
+        assert_eq!(
+            ForecastMethod::Quantile {
+                quantile: 0.5,
+                days: 2
+            }
+            .forecast(&past, 1, 3),
+            [2.5]
+        );
+        // Smoothed from the oldest day: 0.5 * 4 + 0.5 * 1
+        assert_eq!(
+            ForecastMethod::ExponentialSmoothing {
+                alpha: 0.5,
+                days: 2
+            }
+            .forecast(&past, 1, 3),
+            [2.5]
+        );
+    }
+}
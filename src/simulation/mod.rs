@@ -1,4 +1,22 @@
+pub mod auxiliary;
+pub mod battery;
+pub mod budget;
 pub mod config;
+pub mod degradation;
+pub mod derating;
 pub mod engine;
+pub mod fleet;
+pub mod forecast;
+pub mod optimizer;
+pub mod priority;
+pub mod pv;
+pub mod rainflow;
+pub mod recovery;
+pub mod reserve;
+pub mod strategy;
 pub mod summary;
+pub mod tariff;
+pub mod thermal;
 pub mod tick_result;
+pub mod transformer;
+pub mod unit;
@@ -0,0 +1,434 @@
+use super::config::SimulationConfig;
+use super::engine::{SimulationRun, run_simulation_with};
+use super::strategy::Setpoints;
+use super::strategy::schedule::Schedule;
+use crate::model::mergedseries::MergedTick;
+use anyhow::{Context, Result};
+use chrono::Datelike;
+use minilp::{ComparisonOp, OptimizationDirection, Problem, Variable};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizerConfig {
+    pub enabled: bool,           // run the benchmark next to the configured strategy
+    pub horizon_days: usize,     // days per LP; SoC and monthly peaks carry over
+    pub resolution_minutes: f64, // battery power is constant within a block
+    pub srl_shortfall_penalty_chf_per_kwh: f64, // SRL activations are an obligation
+    pub transformer_penalty_chf_per_kwh: f64, // for what the battery cannot keep below the limit
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            horizon_days: 1,
+            resolution_minutes: 15.0,
+            srl_shortfall_penalty_chf_per_kwh: 1.0,
+            transformer_penalty_chf_per_kwh: 10.0,
+        }
+    }
+}
+
+/// Consecutive ticks the LP treats as one step.
+struct Block<'a> {
+    ticks: &'a [MergedTick],
+    hours: f64,
+    max_load_kw: f64,
+    min_load_kw: f64,
+    limit_kw: f64, // active power the transformer leaves next to the site's reactive power
+    energy_price_chf_per_kwh: f64,
+    srl_pos_kwh: f64,
+    srl_neg_kwh: f64,       // absorbed, positive
+    srl_pos_kw: (f64, f64), // lowest and highest activation of a tick
+    srl_neg_kw: (f64, f64),
+    srl_pos_price_chf_per_kwh: f64,
+    srl_neg_price_chf_per_kwh: f64, // paid by the battery, negative prices earn
+}
+
+impl<'a> Block<'a> {
+    fn new(ticks: &'a [MergedTick], config: &SimulationConfig, timestep_h: f64) -> Self {
+        let srl_pos_kwh: f64 = ticks.iter().map(|t| t.srl_pos_kwh.max(0.0)).sum();
+        let srl_neg_kwh: f64 = ticks.iter().map(|t| (-t.srl_neg_kwh).max(0.0)).sum();
+        // Energy-weighted prices of the activations
+        let weighted = |energy: f64, value: f64| if energy > 0.0 { value / energy } else { 0.0 };
+        let range = |kwh: fn(&MergedTick) -> f64| {
+            ticks.iter().fold((f64::MAX, f64::MIN), |(lo, hi), t| {
+                (lo.min(kwh(t) / timestep_h), hi.max(kwh(t) / timestep_h))
+            })
+        };
+
+        Self {
+            ticks,
+            hours: ticks.len() as f64 * timestep_h,
+            max_load_kw: ticks.iter().map(|t| t.power_kw).fold(f64::MIN, f64::max),
+            min_load_kw: ticks.iter().map(|t| t.power_kw).fold(f64::MAX, f64::min),
+            limit_kw: ticks
+                .iter()
+                .map(|t| {
+                    (config.transformer_limit_kva.powi(2) - config.site_reactive_kvar(t).powi(2))
+                        .max(0.0)
+                        .sqrt()
+                })
+                .fold(f64::INFINITY, f64::min),
+            energy_price_chf_per_kwh: ticks
+                .iter()
+                .map(|t| config.energy_price_chf_per_kwh(t.timestamp))
+                .sum::<f64>()
+                / ticks.len() as f64,
+            srl_pos_kwh,
+            srl_neg_kwh,
+            srl_pos_kw: range(|t| t.srl_pos_kwh.max(0.0)),
+            srl_neg_kw: range(|t| (-t.srl_neg_kwh).max(0.0)),
+            srl_pos_price_chf_per_kwh: weighted(
+                srl_pos_kwh,
+                ticks
+                    .iter()
+                    .map(|t| t.srl_pos_price_eur_mwh / 1000.0 * t.srl_pos_kwh.max(0.0))
+                    .sum(),
+            ),
+            srl_neg_price_chf_per_kwh: weighted(
+                srl_neg_kwh,
+                ticks
+                    .iter()
+                    .map(|t| t.srl_neg_price_eur_mwh / 1000.0 * (-t.srl_neg_kwh).max(0.0))
+                    .sum(),
+            ),
+        }
+    }
+
+    /// Grid power per kWh of SRL served in the block, at its lowest and highest tick.
+    ///
+    /// A shortfall scales every tick of the block alike, so these bound the grid power of
+    /// each tick in the LP.
+    fn srl_kw_per_kwh(kw: (f64, f64), kwh: f64) -> (f64, f64) {
+        if kwh > 0.0 {
+            (kw.0 / kwh, kw.1 / kwh)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    fn month(&self) -> (i32, u32) {
+        let timestamp = self.ticks[0].timestamp;
+        (timestamp.year(), timestamp.month())
+    }
+}
+
+/// Variables of one block.
+struct BlockVars {
+    charge_kw: Variable, // peak shaving / energy shifting, grid side
+    discharge_kw: Variable,
+    soc_kwh: Variable,     // at the end of the block
+    srl_pos_kwh: Variable, // served
+    srl_neg_kwh: Variable,
+}
+
+/// SoC window and power limits the LP plans with; the fleet is one battery.
+pub struct FleetLimits {
+    pub floor_kwh: f64, // peak shaving stays outside the SRL reserve
+    pub ceiling_kwh: f64,
+    pub charge_limit_kw: f64,
+    pub discharge_limit_kw: f64,
+    pub efficiency: f64, // one way
+}
+
+impl FleetLimits {
+    /// Nameplate capacity, nominal efficiency and rated power.
+    pub fn nominal(config: &SimulationConfig) -> Self {
+        let capacity_kwh = config.capacity_kwh();
+        let soc_min_kwh: f64 = config
+            .units
+            .iter()
+            .map(|u| u.capacity_kwh * u.min_soc_frac)
+            .sum();
+        let (reserve_pos_kwh, reserve_neg_kwh) =
+            config
+                .srl_reserve
+                .unit_kwh(capacity_kwh - soc_min_kwh, 1.0, config.efficiency());
+
+        Self {
+            floor_kwh: soc_min_kwh + reserve_pos_kwh,
+            ceiling_kwh: capacity_kwh - reserve_neg_kwh,
+            charge_limit_kw: config
+                .units
+                .iter()
+                .map(|u| (u.capacity_kwh * u.charge_c_rate).min(u.inverter_kva))
+                .sum(),
+            discharge_limit_kw: config
+                .units
+                .iter()
+                .map(|u| (u.capacity_kwh * u.discharge_c_rate).min(u.inverter_kva))
+                .sum(),
+            efficiency: config.efficiency(),
+        }
+    }
+}
+
+/// Peak shaving / energy shifting power of one block, grid side, and the SRL it serves.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockPlan {
+    pub charge_kw: f64,
+    pub discharge_kw: f64,
+    pub srl_pos_share: f64, // of the activations in the block
+    pub srl_neg_share: f64,
+}
+
+/// Solution of one horizon.
+pub struct HorizonPlan {
+    pub blocks: Vec<BlockPlan>,
+    pub end_soc_kwh: f64,
+    pub month_peaks_kw: HashMap<(i32, u32), f64>, // grid peak per billing month
+}
+
+/// Perfect-foresight dispatch as a benchmark for the heuristic strategies.
+///
+/// Solves the horizon as an LP and replays the plan through the engine, so the results
+/// contain the same losses, aging and limits as any other run.
+pub fn run_optimal(ticks: &[MergedTick], config: &SimulationConfig) -> Result<SimulationRun> {
+    let plan = optimal_setpoints(ticks, config)?;
+    Ok(run_simulation_with(ticks, config, &mut Schedule::new(plan)))
+}
+
+/// Solves the whole series in horizons of `horizon_days`, carrying SoC and monthly peaks.
+///
+/// Each tick requests the share of its SRL activation the LP serves in the block, so the
+/// replay follows the planned SoC path.
+pub fn optimal_setpoints(
+    ticks: &[MergedTick],
+    config: &SimulationConfig,
+) -> Result<Vec<Setpoints>> {
+    let opt = &config.optimizer;
+    let timestep_h = config.timestep_minutes / 60.0;
+    let ticks_per_block =
+        ((opt.resolution_minutes / config.timestep_minutes).round() as usize).max(1);
+    let limits = FleetLimits::nominal(config);
+
+    let mut soc_kwh: f64 = config
+        .units
+        .iter()
+        .map(|u| u.capacity_kwh * u.initial_soc_frac)
+        .sum();
+    let mut month_peaks_kw: HashMap<(i32, u32), f64> = HashMap::new();
+    let mut plan = Vec::with_capacity(ticks.len());
+
+    let mut start = 0;
+    while start < ticks.len() {
+        let first_day = ticks[start].timestamp.date_naive();
+        let len = ticks[start..]
+            .iter()
+            .take_while(|t| {
+                (t.timestamp.date_naive() - first_day).num_days() < opt.horizon_days.max(1) as i64
+            })
+            .count();
+        let horizon = &ticks[start..start + len];
+
+        let solution = plan_horizon(
+            horizon,
+            ticks_per_block,
+            config,
+            &limits,
+            soc_kwh,
+            &month_peaks_kw,
+        )
+        .with_context(|| format!("LP for the horizon starting {}", first_day))?;
+
+        for (block, ticks) in solution.blocks.iter().zip(horizon.chunks(ticks_per_block)) {
+            for tick in ticks {
+                plan.push(Setpoints {
+                    srl_pos_kwh: tick.srl_pos_kwh.max(0.0) * block.srl_pos_share,
+                    srl_neg_kwh: (-tick.srl_neg_kwh).max(0.0) * block.srl_neg_share,
+                    ps_discharge_kwh: block.discharge_kw * timestep_h,
+                    ps_charge_kwh: block.charge_kw * timestep_h,
+                    ..Default::default()
+                });
+            }
+        }
+        soc_kwh = solution.end_soc_kwh;
+        month_peaks_kw = solution.month_peaks_kw;
+
+        start += len;
+    }
+
+    Ok(plan)
+}
+
+/// Maximises SRL revenue minus demand charges and energy cost (which covers the losses).
+///
+/// Battery power is constant within a block of `ticks_per_block`, the peak of a block is
+/// its highest tick, SRL included as in the engine's grid power. The whole plan, SRL too,
+/// stays within the window outside the SRL reserve: the LP cannot tell when peak shaving
+/// may follow SRL below the floor, and a plan in the narrower window is one the engine can
+/// always replay. Peak shaving does not export. Monthly peaks start at `month_peaks_kw`,
+/// the SoC at the end of the horizon is at least the start SoC.
+pub fn plan_horizon(
+    ticks: &[MergedTick],
+    ticks_per_block: usize,
+    config: &SimulationConfig,
+    limits: &FleetLimits,
+    soc_kwh: f64,
+    month_peaks_kw: &HashMap<(i32, u32), f64>,
+) -> Result<HorizonPlan> {
+    let opt = &config.optimizer;
+    let timestep_h = config.timestep_minutes / 60.0;
+    let eff = limits.efficiency;
+    let blocks: Vec<Block> = ticks
+        .chunks(ticks_per_block.max(1))
+        .map(|b| Block::new(b, config, timestep_h))
+        .collect();
+
+    let mut problem = Problem::new(OptimizationDirection::Maximize);
+
+    // One peak per billing month, at least what was already set
+    let mut peaks: HashMap<(i32, u32), Variable> = HashMap::new();
+    for block in &blocks {
+        peaks.entry(block.month()).or_insert_with(|| {
+            let set_kw = month_peaks_kw.get(&block.month()).copied().unwrap_or(0.0);
+            problem.add_var(
+                -config.demand_charge_chf_per_kw_month,
+                (set_kw, f64::INFINITY),
+            )
+        });
+    }
+
+    let soc_start_kwh = soc_kwh.clamp(limits.floor_kwh, limits.ceiling_kwh.max(limits.floor_kwh));
+    let mut vars: Vec<BlockVars> = Vec::with_capacity(blocks.len());
+    for block in &blocks {
+        let h = block.hours;
+        let price = block.energy_price_chf_per_kwh;
+
+        let v = BlockVars {
+            charge_kw: problem.add_var(-price * h, (0.0, limits.charge_limit_kw)),
+            discharge_kw: problem.add_var(price * h, (0.0, limits.discharge_limit_kw)),
+            soc_kwh: problem.add_var(0.0, (limits.floor_kwh, limits.ceiling_kwh)),
+            // SRL energy actually served, the shortfall is penalised
+            srl_pos_kwh: problem.add_var(
+                block.srl_pos_price_chf_per_kwh + opt.srl_shortfall_penalty_chf_per_kwh,
+                (0.0, block.srl_pos_kwh),
+            ),
+            srl_neg_kwh: problem.add_var(
+                -block.srl_neg_price_chf_per_kwh + opt.srl_shortfall_penalty_chf_per_kwh,
+                (0.0, block.srl_neg_kwh),
+            ),
+        };
+        let (srl_pos_min, srl_pos_max) = Block::srl_kw_per_kwh(block.srl_pos_kw, block.srl_pos_kwh);
+        let (srl_neg_min, srl_neg_max) = Block::srl_kw_per_kwh(block.srl_neg_kw, block.srl_neg_kwh);
+
+        // SoC balance: s_b - s_{b-1} - eff * in + out / eff = 0
+        let mut balance = vec![
+            (v.soc_kwh, 1.0),
+            (v.charge_kw, -eff * h),
+            (v.srl_neg_kwh, -eff),
+            (v.discharge_kw, h / eff),
+            (v.srl_pos_kwh, 1.0 / eff),
+        ];
+        let previous_soc_kwh = match vars.last() {
+            Some(previous) => {
+                balance.push((previous.soc_kwh, -1.0));
+                0.0
+            }
+            None => soc_start_kwh,
+        };
+        problem.add_constraint(balance, ComparisonOp::Eq, previous_soc_kwh);
+
+        // Services share the power limits
+        problem.add_constraint(
+            [(v.charge_kw, 1.0), (v.srl_neg_kwh, 1.0 / h)],
+            ComparisonOp::Le,
+            limits.charge_limit_kw,
+        );
+        problem.add_constraint(
+            [(v.discharge_kw, 1.0), (v.srl_pos_kwh, 1.0 / h)],
+            ComparisonOp::Le,
+            limits.discharge_limit_kw,
+        );
+
+        // Grid = load + charge - discharge + SRL neg - SRL pos, peak shaving does not export
+        problem.add_constraint(
+            [(v.discharge_kw, 1.0), (v.charge_kw, -1.0)],
+            ComparisonOp::Le,
+            block.min_load_kw.max(0.0),
+        );
+        problem.add_constraint(
+            [
+                (peaks[&block.month()], 1.0),
+                (v.charge_kw, -1.0),
+                (v.discharge_kw, 1.0),
+                (v.srl_neg_kwh, -srl_neg_max),
+                (v.srl_pos_kwh, srl_pos_min),
+            ],
+            ComparisonOp::Ge,
+            block.max_load_kw,
+        );
+
+        // Transformer limit, softened where the battery alone cannot hold it
+        if config.enforce_transformer_limit {
+            let excess_kw = problem.add_var(
+                -opt.transformer_penalty_chf_per_kwh * h,
+                (0.0, f64::INFINITY),
+            );
+            problem.add_constraint(
+                [
+                    (v.charge_kw, 1.0),
+                    (v.discharge_kw, -1.0),
+                    (v.srl_neg_kwh, srl_neg_max),
+                    (v.srl_pos_kwh, -srl_pos_min),
+                    (excess_kw, -1.0),
+                ],
+                ComparisonOp::Le,
+                block.limit_kw - block.max_load_kw,
+            );
+            problem.add_constraint(
+                [
+                    (v.charge_kw, -1.0),
+                    (v.discharge_kw, 1.0),
+                    (v.srl_neg_kwh, -srl_neg_min),
+                    (v.srl_pos_kwh, srl_pos_max),
+                    (excess_kw, -1.0),
+                ],
+                ComparisonOp::Le,
+                block.limit_kw + block.min_load_kw,
+            );
+        }
+
+        vars.push(v);
+    }
+
+    // Leave at least the start SoC for what follows
+    if let Some(last) = vars.last() {
+        problem.add_constraint([(last.soc_kwh, 1.0)], ComparisonOp::Ge, soc_start_kwh);
+    }
+
+    let solution = problem.solve()?;
+    let value = |var: Variable| {
+        let x = *solution.var_value(var);
+        if x > 1e-9 { x } else { 0.0 }
+    };
+    let share = |served: Variable, requested_kwh: f64| {
+        if requested_kwh > 0.0 {
+            (value(served) / requested_kwh).min(1.0)
+        } else {
+            0.0
+        }
+    };
+
+    Ok(HorizonPlan {
+        blocks: vars
+            .iter()
+            .zip(&blocks)
+            .map(|(v, block)| BlockPlan {
+                charge_kw: value(v.charge_kw),
+                discharge_kw: value(v.discharge_kw),
+                srl_pos_share: share(v.srl_pos_kwh, block.srl_pos_kwh),
+                srl_neg_share: share(v.srl_neg_kwh, block.srl_neg_kwh),
+            })
+            .collect(),
+        end_soc_kwh: vars
+            .last()
+            .map_or(soc_start_kwh, |last| *solution.var_value(last.soc_kwh)),
+        month_peaks_kw: peaks
+            .into_iter()
+            .map(|(month, peak)| (month, *solution.var_value(peak)))
+            .collect(),
+    })
+}
@@ -0,0 +1,119 @@
+use super::config::SimulationConfig;
+use super::strategy::{BatteryState, Setpoints};
+use crate::model::mergedseries::MergedTick;
+use serde::{Deserialize, Serialize};
+
+/// Order in which SRL and peak shaving draw from the fleet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ServicePriority {
+    SrlFirst,
+    PeakShavingFirst,
+    /// Per tick, whichever has the higher marginal value when both compete.
+    ValueBased {
+        srl_shortfall_penalty_chf_per_kwh: f64, // on top of the lost SRL revenue
+    },
+}
+
+/// A service the priority orders.
+#[derive(Debug, Clone, Copy)]
+pub enum Service {
+    Srl,
+    PeakShaving,
+}
+
+/// SRL and peak shaving asking for the same direction beyond what the fleet can do.
+#[derive(Debug, Clone, Copy)]
+pub struct Competition {
+    pub discharge: bool, // SRL pos against peak shaving discharge, else the charge side
+    pub srl_chf_per_kwh: f64, // marginal value of each service
+    pub ps_chf_per_kwh: f64,
+    pub ps_contested_kwh: f64, // what peak shaving loses when SRL goes first
+}
+
+impl Competition {
+    /// Detects competition in this tick and values both services.
+    ///
+    /// Peak shaving only draws from outside the SRL reserve, so the services compete once
+    /// SRL going first leaves peak shaving less than it could get on its own. The contested
+    /// peak shaving energy is worth the demand charge on how much it lowers the month's
+    /// running peak; below that peak, and when absorbing export, it saves nothing.
+    pub fn detect(
+        tick: &MergedTick,
+        setpoints: &Setpoints,
+        battery: &BatteryState,
+        grid_kw: f64,
+        month_peak_kw: f64,
+        config: &SimulationConfig,
+        timestep_h: f64,
+    ) -> Option<Self> {
+        let penalty = config.service_priority.srl_shortfall_penalty_chf_per_kwh();
+        let discharge = setpoints.srl_pos_kwh > 0.0
+            && setpoints.ps_discharge_kwh > 0.0
+            && battery.outside_reserve_discharge_kwh > 0.0
+            && setpoints.srl_pos_kwh + setpoints.ps_discharge_kwh
+                > battery.outside_reserve_discharge_kwh;
+        let charge = setpoints.srl_neg_kwh > 0.0
+            && setpoints.ps_charge_kwh > 0.0
+            && battery.outside_reserve_charge_kwh > 0.0
+            && setpoints.srl_neg_kwh + setpoints.ps_charge_kwh > battery.outside_reserve_charge_kwh;
+
+        if discharge {
+            let outside_kwh = battery.outside_reserve_discharge_kwh;
+            let srl_kwh = setpoints.srl_pos_kwh.min(battery.max_discharge_kwh);
+            let ps_alone_kwh = setpoints.ps_discharge_kwh.min(outside_kwh);
+            let ps_after_srl_kwh = (outside_kwh - srl_kwh).clamp(0.0, ps_alone_kwh);
+            let srl_after_ps_kwh = setpoints
+                .srl_pos_kwh
+                .min(battery.max_discharge_kwh - ps_alone_kwh);
+
+            // SRL pos lowers the grid as well, so compare the peaks of both orders
+            let peak_kw = |battery_kwh: f64| month_peak_kw.max(grid_kw - battery_kwh / timestep_h);
+            let peak_rise_kw =
+                peak_kw(srl_kwh + ps_after_srl_kwh) - peak_kw(ps_alone_kwh + srl_after_ps_kwh);
+            let ps_contested_kwh = ps_alone_kwh - ps_after_srl_kwh;
+
+            Some(Self {
+                discharge,
+                srl_chf_per_kwh: tick.srl_pos_price_eur_mwh / 1000.0 + penalty,
+                ps_chf_per_kwh: config.demand_charge_chf_per_kw_month * peak_rise_kw.max(0.0)
+                    / ps_contested_kwh,
+                ps_contested_kwh,
+            })
+        } else if charge {
+            let outside_kwh = battery.outside_reserve_charge_kwh;
+            let ps_alone_kwh = setpoints.ps_charge_kwh.min(outside_kwh);
+            let srl_kwh = setpoints.srl_neg_kwh.min(battery.max_charge_kwh);
+            Some(Self {
+                discharge,
+                srl_chf_per_kwh: -tick.srl_neg_price_eur_mwh / 1000.0 + penalty,
+                ps_chf_per_kwh: 0.0,
+                ps_contested_kwh: ps_alone_kwh - (outside_kwh - srl_kwh).clamp(0.0, ps_alone_kwh),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl ServicePriority {
+    /// Only the value-based mode prices an SRL shortfall beyond the lost revenue.
+    pub fn srl_shortfall_penalty_chf_per_kwh(&self) -> f64 {
+        match self {
+            ServicePriority::ValueBased {
+                srl_shortfall_penalty_chf_per_kwh,
+            } => *srl_shortfall_penalty_chf_per_kwh,
+            _ => 0.0,
+        }
+    }
+
+    /// Whether peak shaving is served before SRL in this tick.
+    pub fn peak_shaving_first(&self, competition: Option<&Competition>) -> bool {
+        match self {
+            ServicePriority::SrlFirst => false,
+            ServicePriority::PeakShavingFirst => true,
+            ServicePriority::ValueBased { .. } => {
+                competition.is_some_and(|c| c.ps_chf_per_kwh > c.srl_chf_per_kwh)
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use super::config::SimulationConfig;
+use super::tick_result::SimulationTickResult;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PvConfig {
+    pub peak_kw: Option<f64>, // installed PV power, default: peak of the PV series
+    pub feed_in_limit_fraction: Option<f64>, // export cap as share of peak_kw, e.g. 0.7
+    pub feed_in_tariff_chf_per_kwh: f64,
+}
+
+impl Default for PvConfig {
+    fn default() -> Self {
+        Self {
+            peak_kw: None,
+            feed_in_limit_fraction: None,
+            feed_in_tariff_chf_per_kwh: 0.08,
+        }
+    }
+}
+
+impl PvConfig {
+    /// Export limit in kW, infinite without a feed-in limit.
+    pub fn feed_in_limit_kw(&self, series_peak_kw: f64) -> f64 {
+        match self.feed_in_limit_fraction {
+            Some(fraction) => fraction * self.peak_kw.unwrap_or(series_peak_kw),
+            None => f64::INFINITY,
+        }
+    }
+}
+
+/// Self-consumption KPIs of a site with PV, with the battery and for the same site without it.
+///
+/// Import and export are taken from the grid net power, so arbitrage shows up here as well.
+/// Curtailed PV counts as neither used on site nor exported.
+#[derive(Debug)]
+pub struct PvKpis {
+    pub pv_kwh: f64,
+    pub consumption_kwh: f64, // site load, without the battery's auxiliary load
+
+    pub import_kwh: f64,
+    pub export_kwh: f64,
+    pub import_without_battery_kwh: f64,
+    pub export_without_battery_kwh: f64,
+
+    // Feed-in limit
+    pub absorbed_kwh: f64, // export above the limit charged into the battery
+    pub curtailed_kwh: f64,
+    pub curtailed_without_battery_kwh: f64,
+    pub lost_feed_in_revenue_chf: f64,
+    pub lost_feed_in_revenue_without_battery_chf: f64,
+
+    pub self_consumption_ratio: f64, // share of PV used on site
+    pub self_consumption_ratio_without_battery: f64,
+    pub autarky_rate: f64, // share of consumption not drawn from the grid
+    pub autarky_rate_without_battery: f64,
+}
+
+/// `None` if no PV series was imported.
+pub fn pv_kpis(
+    ticks: &[SimulationTickResult],
+    config: &SimulationConfig,
+    timestep_h: f64,
+) -> Option<PvKpis> {
+    if ticks.iter().all(|t| t.pv_kw.is_none()) {
+        return None;
+    }
+
+    let series_peak_kw = ticks.iter().filter_map(|t| t.pv_kw).fold(0.0, f64::max);
+    let feed_in_limit_kw = config.pv.feed_in_limit_kw(series_peak_kw);
+
+    let mut kpis = PvKpis {
+        pv_kwh: 0.0,
+        consumption_kwh: 0.0,
+        import_kwh: 0.0,
+        export_kwh: 0.0,
+        import_without_battery_kwh: 0.0,
+        export_without_battery_kwh: 0.0,
+        absorbed_kwh: 0.0,
+        curtailed_kwh: 0.0,
+        curtailed_without_battery_kwh: 0.0,
+        lost_feed_in_revenue_chf: 0.0,
+        lost_feed_in_revenue_without_battery_chf: 0.0,
+        self_consumption_ratio: 0.0,
+        self_consumption_ratio_without_battery: 0.0,
+        autarky_rate: 0.0,
+        autarky_rate_without_battery: 0.0,
+    };
+
+    for tick in ticks {
+        let pv_kw = tick.pv_kw.unwrap_or(0.0);
+        kpis.pv_kwh += pv_kw * timestep_h;
+        kpis.consumption_kwh += (tick.original_power_kw + pv_kw) * timestep_h;
+
+        kpis.import_kwh += tick.grid_net_kw.max(0.0) * timestep_h;
+        kpis.export_kwh += (-tick.grid_net_kw).max(0.0) * timestep_h;
+        kpis.absorbed_kwh += tick.pv_absorbed_kwh;
+        kpis.curtailed_kwh += tick.pv_curtailed_kwh;
+
+        // Without battery everything above the limit is curtailed
+        let export_without_battery_kw = (-tick.original_power_kw).max(0.0);
+        let curtailed_without_battery_kw = (export_without_battery_kw - feed_in_limit_kw).max(0.0);
+        kpis.import_without_battery_kwh += tick.original_power_kw.max(0.0) * timestep_h;
+        kpis.export_without_battery_kwh +=
+            (export_without_battery_kw - curtailed_without_battery_kw) * timestep_h;
+        kpis.curtailed_without_battery_kwh += curtailed_without_battery_kw * timestep_h;
+    }
+
+    let tariff = config.pv.feed_in_tariff_chf_per_kwh;
+    kpis.lost_feed_in_revenue_chf = kpis.curtailed_kwh * tariff;
+    kpis.lost_feed_in_revenue_without_battery_chf = kpis.curtailed_without_battery_kwh * tariff;
+
+    let self_consumption = |export_kwh: f64, curtailed_kwh: f64| {
+        if kpis.pv_kwh > 0.0 {
+            (1.0 - (export_kwh + curtailed_kwh) / kpis.pv_kwh).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+    let autarky = |import_kwh: f64| {
+        if kpis.consumption_kwh > 0.0 {
+            (1.0 - import_kwh / kpis.consumption_kwh).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+
+    kpis.self_consumption_ratio = self_consumption(kpis.export_kwh, kpis.curtailed_kwh);
+    kpis.self_consumption_ratio_without_battery = self_consumption(
+        kpis.export_without_battery_kwh,
+        kpis.curtailed_without_battery_kwh,
+    );
+    kpis.autarky_rate = autarky(kpis.import_kwh);
+    kpis.autarky_rate_without_battery = autarky(kpis.import_without_battery_kwh);
+
+    Some(kpis)
+}
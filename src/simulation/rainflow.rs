@@ -0,0 +1,233 @@
+use serde::Serialize;
+
+/// One counted cycle (count 1.0) or half cycle (count 0.5) between two turning points.
+#[derive(Debug, Clone, Copy)]
+pub struct RainflowCycle<T = usize> {
+    pub range: f64,
+    pub count: f64,
+    pub start: T, // tag of the turning point where the cycle began, e.g. its index
+    pub end: T,
+}
+
+/// Cycle depth histogram bin (depth in % of nameplate capacity).
+#[derive(Debug, Serialize)]
+pub struct CycleBin {
+    pub depth_from_pct: f64,
+    pub depth_to_pct: f64,
+    pub cycles: f64,
+    pub equivalent_full_cycles: f64,
+}
+
+/// Cycles counted per calendar month (by start of the cycle).
+#[derive(Debug, Serialize)]
+pub struct MonthlyCycles {
+    pub month: String,
+    pub cycles: f64,
+    pub equivalent_full_cycles: f64,
+}
+
+/// Streaming ASTM E1049 rainflow counter.
+///
+/// Values come in one at a time, each with a tag (an index, a timestamp, ...) that is
+/// handed back with the cycles. Consecutive values in the same direction are reduced to
+/// their turning point first.
+#[derive(Clone)]
+pub struct RainflowCounter<T> {
+    pending: Option<(f64, T)>, // latest extreme, not yet confirmed as turning point
+    direction: f64,            // sign of the segment ending in `pending`
+    stack: Vec<(f64, T)>,
+}
+
+impl<T> Default for RainflowCounter<T> {
+    fn default() -> Self {
+        Self {
+            pending: None,
+            direction: 0.0,
+            stack: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy> RainflowCounter<T> {
+    /// Adds the next value and returns the cycles it closes.
+    pub fn push(&mut self, value: f64, tag: T) -> Vec<RainflowCycle<T>> {
+        let epsilon = 1e-9;
+        let Some((last, _)) = self.pending else {
+            self.pending = Some((value, tag));
+            return Vec::new();
+        };
+
+        if (value - last).abs() <= epsilon {
+            return Vec::new();
+        }
+
+        // Same direction as the last segment: extend it instead of adding a point
+        let direction = (value - last).signum();
+        let previous = self.pending.replace((value, tag));
+        if direction == self.direction {
+            return Vec::new();
+        }
+        self.direction = direction;
+
+        previous.map_or_else(Vec::new, |point| self.count(point))
+    }
+
+    /// Counts everything left at the end of the series: the last turning point, then the
+    /// residue as half cycles.
+    pub fn finish(mut self) -> Vec<RainflowCycle<T>> {
+        let mut cycles = match self.pending.take() {
+            Some(point) => self.count(point),
+            None => Vec::new(),
+        };
+
+        for pair in self.stack.windows(2) {
+            cycles.push(RainflowCycle {
+                range: (pair[1].0 - pair[0].0).abs(),
+                count: 0.5,
+                start: pair[0].1,
+                end: pair[1].1,
+            });
+        }
+
+        cycles
+    }
+
+    /// What `finish` would count if the series ended now, without ending it.
+    pub fn residue(&self) -> Vec<RainflowCycle<T>> {
+        self.clone().finish()
+    }
+
+    /// Three-point step for a confirmed turning point.
+    fn count(&mut self, point: (f64, T)) -> Vec<RainflowCycle<T>> {
+        let mut cycles = Vec::new();
+        let stack = &mut self.stack;
+        stack.push(point);
+
+        while stack.len() >= 3 {
+            let n = stack.len();
+            let x = (stack[n - 1].0 - stack[n - 2].0).abs();
+            let y = (stack[n - 2].0 - stack[n - 3].0).abs();
+
+            if x < y {
+                break;
+            }
+
+            if n == 3 {
+                // Range Y contains the starting point: half cycle, drop the first point
+                cycles.push(RainflowCycle {
+                    range: y,
+                    count: 0.5,
+                    start: stack[0].1,
+                    end: stack[1].1,
+                });
+                stack.remove(0);
+            } else {
+                // Full cycle: drop both points of range Y
+                cycles.push(RainflowCycle {
+                    range: y,
+                    count: 1.0,
+                    start: stack[n - 3].1,
+                    end: stack[n - 2].1,
+                });
+                stack.drain(n - 3..n - 1);
+            }
+        }
+
+        cycles
+    }
+}
+
+/// ASTM E1049 rainflow counting over a SoC (or any) trajectory, tagged with the index.
+pub fn rainflow(series: &[f64]) -> Vec<RainflowCycle> {
+    let mut counter = RainflowCounter::default();
+    let mut cycles = Vec::new();
+
+    for (i, &value) in series.iter().enumerate() {
+        cycles.extend(counter.push(value, i));
+    }
+    cycles.extend(counter.finish());
+
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Range and count of every cycle, sorted by range.
+    fn ranges(cycles: &[RainflowCycle]) -> Vec<(f64, f64)> {
+        let mut ranges: Vec<(f64, f64)> = cycles.iter().map(|c| (c.range, c.count)).collect();
+        ranges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        ranges
+    }
+
+    #[test]
+    fn astm_e1049_reference_sequence() {
+        // ASTM E1049-85, figure 6: A..I
+        let cycles = rainflow(&[-2.0, 1.0, -3.0, 5.0, -1.0, 3.0, -4.0, 4.0, -2.0]);
+
+        assert_eq!(
+            ranges(&cycles),
+            vec![
+                (3.0, 0.5),
+                (4.0, 0.5),
+                (4.0, 1.0),
+                (6.0, 0.5),
+                (8.0, 0.5),
+                (8.0, 0.5),
+                (9.0, 0.5),
+            ]
+        );
+
+        // Table of the standard: counts per range
+        let count = |range: f64| {
+            cycles
+                .iter()
+                .filter(|c| c.range == range)
+                .map(|c| c.count)
+                .sum::<f64>()
+        };
+        assert_eq!(
+            [3.0, 4.0, 6.0, 8.0, 9.0].map(count),
+            [0.5, 1.5, 0.5, 1.0, 0.5]
+        );
+
+        // E-F is the only full cycle
+        let full: Vec<(usize, usize)> = cycles
+            .iter()
+            .filter(|c| c.count == 1.0)
+            .map(|c| (c.start, c.end))
+            .collect();
+        assert_eq!(full, vec![(4, 5)]);
+    }
+
+    #[test]
+    fn points_between_turning_points_are_dropped() {
+        let coarse = rainflow(&[-2.0, 1.0, -3.0, 5.0, -1.0, 3.0, -4.0, 4.0, -2.0]);
+        let fine = rainflow(&[
+            -2.0, 0.0, 1.0, 1.0, -3.0, 0.0, 5.0, -1.0, 3.0, -4.0, 0.0, 4.0, -2.0,
+        ]);
+
+        assert_eq!(ranges(&coarse), ranges(&fine));
+    }
+
+    #[test]
+    fn residue_does_not_end_the_count() {
+        let series = [-2.0, 1.0, -3.0, 5.0, -1.0, 3.0, -4.0, 4.0, -2.0];
+        let mut counter = RainflowCounter::default();
+        let mut closed = Vec::new();
+        for (i, &value) in series[..6].iter().enumerate() {
+            closed.extend(counter.push(value, i));
+        }
+
+        // Ending here counts everything open as half cycles
+        let residue = counter.residue();
+        assert!(residue.iter().all(|c| c.count == 0.5));
+
+        for (i, &value) in series.iter().enumerate().skip(6) {
+            closed.extend(counter.push(value, i));
+        }
+        closed.extend(counter.finish());
+        assert_eq!(ranges(&closed), ranges(&rainflow(&series)));
+    }
+}
@@ -0,0 +1,61 @@
+use super::strategy::BatteryState;
+use serde::{Deserialize, Serialize};
+
+/// SoC management in idle ticks: brings the SoC back into a target band, so the battery
+/// is not left empty after peak shaving (or full after absorbing).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocRecoveryConfig {
+    pub enabled: bool,
+    pub target_min_frac: f64, // band within the SoC window, 0 = soc_min, 1 = soc_max
+    pub target_max_frac: f64,
+    pub max_power_kw: f64,    // grid side
+    pub tariff_windows: bool, // with a tariff calendar: charge in NT only, discharge in HT only
+}
+
+impl Default for SocRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_min_frac: 0.5,
+            target_max_frac: 0.8,
+            max_power_kw: 50.0,
+            tariff_windows: false,
+        }
+    }
+}
+
+impl SocRecoveryConfig {
+    /// Grid-side energy toward the target band this tick: + charge, - discharge.
+    ///
+    /// `high_tariff` is `None` without a tariff calendar. Grid headroom is up to the engine.
+    pub fn requested_kwh(
+        &self,
+        battery: &BatteryState,
+        efficiency: f64,
+        timestep_h: f64,
+        high_tariff: Option<bool>,
+    ) -> f64 {
+        let window_kwh = battery.soc_max_kwh - battery.soc_min_kwh;
+        let low_kwh = battery.soc_min_kwh + self.target_min_frac * window_kwh;
+        let high_kwh = battery.soc_min_kwh + self.target_max_frac * window_kwh;
+        let max_kwh = self.max_power_kw * timestep_h;
+
+        if battery.soc_kwh < low_kwh {
+            if self.tariff_windows && high_tariff == Some(true) {
+                return 0.0;
+            }
+            ((low_kwh - battery.soc_kwh) / efficiency)
+                .min(max_kwh)
+                .min(battery.max_charge_kwh)
+        } else if battery.soc_kwh > high_kwh {
+            if self.tariff_windows && high_tariff == Some(false) {
+                return 0.0;
+            }
+            -((battery.soc_kwh - high_kwh) * efficiency)
+                .min(max_kwh)
+                .min(battery.max_discharge_kwh)
+        } else {
+            0.0
+        }
+    }
+}
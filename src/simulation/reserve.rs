@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// SoC headroom kept free for SRL, separately for both directions.
+///
+/// The positive reserve sits above `soc_min` and keeps energy for SRL pos; the negative
+/// reserve sits below `soc_max` and keeps room for SRL neg. SRL may use the whole window,
+/// every other service stays between the two. Where both overlap, only SRL is left.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SrlReserve {
+    /// Share of each unit's SoC window on both sides.
+    Symmetric { fraction: f64 },
+    /// Stored energy for the whole fleet, split across units by nameplate.
+    Energy { pos_kwh: f64, neg_kwh: f64 },
+    /// Bid power held for `duration_minutes` at the grid; losses are added on top.
+    Bid {
+        pos_mw: f64,
+        neg_mw: f64,
+        duration_minutes: f64,
+    },
+}
+
+impl SrlReserve {
+    /// Positive and negative reserve of one unit in stored kWh.
+    ///
+    /// `share` is the unit's part of the fleet nameplate, `efficiency` its one-way
+    /// efficiency. Each side is capped at the window.
+    pub fn unit_kwh(&self, window_kwh: f64, share: f64, efficiency: f64) -> (f64, f64) {
+        let window_kwh = window_kwh.max(0.0);
+        let (pos_kwh, neg_kwh) = match *self {
+            SrlReserve::Symmetric { fraction } => (window_kwh * fraction, window_kwh * fraction),
+            SrlReserve::Energy { pos_kwh, neg_kwh } => (pos_kwh * share, neg_kwh * share),
+            SrlReserve::Bid {
+                pos_mw,
+                neg_mw,
+                duration_minutes,
+            } => {
+                let hours = duration_minutes / 60.0;
+                (
+                    pos_mw * 1000.0 * hours / efficiency * share,
+                    neg_mw * 1000.0 * hours * efficiency * share,
+                )
+            }
+        };
+        (
+            pos_kwh.clamp(0.0, window_kwh),
+            neg_kwh.clamp(0.0, window_kwh),
+        )
+    }
+}
@@ -0,0 +1,113 @@
+use super::srl_peak_shaving::SrlPeakShaving;
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+/// Threshold at the start of each billing month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MonthlyStart {
+    Static,                              // ps_threshold_kw
+    Targets(Vec<f64>),                   // per calendar month, January first
+    PreviousMonthPeak { fraction: f64 }, // forecast from the load peak of the month before
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptivePeakShavingConfig {
+    pub monthly_start: MonthlyStart,
+    pub recharge: bool, // recharge below the threshold, without setting a new peak
+}
+
+impl Default for AdaptivePeakShavingConfig {
+    fn default() -> Self {
+        Self {
+            monthly_start: MonthlyStart::Static,
+            recharge: true,
+        }
+    }
+}
+
+/// Peak shaving against the running monthly billing peak.
+///
+/// Once the battery cannot hold the threshold, the grid peak of the month is set and the
+/// threshold is raised to it: shaving below that peak saves nothing for the rest of the
+/// month. Each month starts again from `monthly_start`. SRL as in the default strategy.
+pub struct AdaptivePeakShaving {
+    base: SrlPeakShaving,
+    config: AdaptivePeakShavingConfig,
+    efficiency: f64, // one way, to turn SoC into grid energy
+    month: Option<(i32, u32)>,
+    threshold_kw: f64,
+    month_load_peak_kw: f64, // for the forecast of the next month
+}
+
+impl AdaptivePeakShaving {
+    pub fn new(config: &AdaptivePeakShavingConfig, efficiency: f64) -> Self {
+        Self {
+            base: SrlPeakShaving,
+            config: config.clone(),
+            efficiency,
+            month: None,
+            threshold_kw: 0.0,
+            month_load_peak_kw: 0.0,
+        }
+    }
+
+    fn start_month(&mut self, month: (i32, u32), ctx: &DispatchContext) {
+        let static_kw = ctx.config.ps_threshold_kw;
+        self.threshold_kw = match &self.config.monthly_start {
+            MonthlyStart::Static => static_kw,
+            MonthlyStart::Targets(targets) => targets
+                .get(month.1 as usize - 1)
+                .copied()
+                .unwrap_or(static_kw),
+            MonthlyStart::PreviousMonthPeak { fraction } => match self.month {
+                Some(_) => fraction * self.month_load_peak_kw,
+                None => static_kw, // nothing to forecast from yet
+            },
+        };
+        self.month = Some(month);
+        self.month_load_peak_kw = 0.0;
+    }
+}
+
+impl DispatchStrategy for AdaptivePeakShaving {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        let mut setpoints = self.base.dispatch(tick, battery, ctx);
+        setpoints.ps_discharge_kwh = 0.0;
+
+        let month = (tick.timestamp.year(), tick.timestamp.month());
+        if self.month != Some(month) {
+            self.start_month(month, ctx);
+        }
+        self.month_load_peak_kw = self.month_load_peak_kw.max(ctx.load_kw);
+
+        // Peak shaving works outside the SRL reserve
+        let floor_kwh = battery.soc_min_kwh + battery.reserve_pos_kwh;
+        let ceiling_kwh = battery.soc_max_kwh - battery.reserve_neg_kwh;
+
+        if ctx.load_kw > self.threshold_kw {
+            let requested_kwh = (ctx.load_kw - self.threshold_kw) * ctx.timestep_h;
+            let deliverable_kwh = battery
+                .max_discharge_kwh
+                .min((battery.soc_kwh - floor_kwh).max(0.0) * self.efficiency);
+
+            // What the battery cannot shave sets this month's peak
+            let grid_kw = ctx.load_kw - requested_kwh.min(deliverable_kwh) / ctx.timestep_h;
+            self.threshold_kw = self.threshold_kw.max(grid_kw);
+            setpoints.ps_discharge_kwh = requested_kwh;
+        } else if self.config.recharge && setpoints.ps_charge_kwh == 0.0 {
+            let headroom_kwh = (ceiling_kwh - battery.soc_kwh).max(0.0) / self.efficiency;
+            setpoints.ps_charge_kwh = ((self.threshold_kw - ctx.load_kw) * ctx.timestep_h)
+                .min(battery.max_charge_kwh)
+                .min(headroom_kwh);
+        }
+
+        setpoints
+    }
+}
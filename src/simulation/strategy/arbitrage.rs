@@ -0,0 +1,173 @@
+use super::srl_peak_shaving::SrlPeakShaving;
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbitrageConfig {
+    pub max_power_kw: f64,       // grid side, both directions
+    pub max_cycles_per_day: f64, // discharge throughput / usable window
+    pub min_spread_eur_mwh: f64, // after round-trip losses, covers wear
+    pub chf_per_eur: f64,        // revenue is booked in CHF
+}
+
+impl Default for ArbitrageConfig {
+    fn default() -> Self {
+        Self {
+            max_power_kw: 500.0,
+            max_cycles_per_day: 1.0,
+            min_spread_eur_mwh: 10.0,
+            chf_per_eur: 0.94,
+        }
+    }
+}
+
+/// Day-ahead arbitrage on top of the default strategy.
+///
+/// At the first tick of a day the whole day is scheduled from the day-ahead prices
+/// (known since the auction the day before): charge in cheap and discharge in expensive
+/// price periods, outside the SRL reserve, within power and cycle limits.
+pub struct Arbitrage {
+    base: SrlPeakShaving,
+    config: ArbitrageConfig,
+    efficiency: f64, // one way, capacity-weighted over the units
+    day: Option<NaiveDate>,
+    plan_kwh: Vec<f64>, // grid-side energy per tick of the day, + discharge
+}
+
+/// Consecutive ticks with the same price.
+struct PriceBlock {
+    first_tick: usize,
+    ticks: usize,
+    price: f64,
+}
+
+impl Arbitrage {
+    pub fn new(config: &ArbitrageConfig, efficiency: f64) -> Self {
+        Self {
+            base: SrlPeakShaving,
+            config: config.clone(),
+            efficiency,
+            day: None,
+            plan_kwh: Vec::new(),
+        }
+    }
+
+    fn plan_day(&mut self, battery: &BatteryState, ctx: &DispatchContext) {
+        let prices = ctx.spot_prices_today;
+        self.plan_kwh = vec![0.0; prices.len()];
+
+        // Group the day into price periods, ticks without price are skipped
+        let mut blocks: Vec<PriceBlock> = Vec::new();
+        for (i, price) in prices.iter().enumerate() {
+            let Some(price) = *price else { continue };
+            match blocks.last_mut() {
+                Some(b) if b.first_tick + b.ticks == i && b.price == price => b.ticks += 1,
+                _ => blocks.push(PriceBlock {
+                    first_tick: i,
+                    ticks: 1,
+                    price,
+                }),
+            }
+        }
+
+        // Usable window outside the SRL reserve, in battery-side kWh
+        let floor = battery.soc_min_kwh + battery.reserve_pos_kwh;
+        let ceiling = battery.soc_max_kwh - battery.reserve_neg_kwh;
+        if ceiling <= floor || blocks.len() < 2 {
+            return;
+        }
+        let eff = self.efficiency;
+        let soc_start = battery.soc_kwh.clamp(floor, ceiling);
+
+        // State per block: SoC at the end of the block and grid energy planned so far
+        let mut soc_after = vec![soc_start; blocks.len()];
+        let mut energy = vec![0.0; blocks.len()];
+        let mut throughput_left = self.config.max_cycles_per_day * (ceiling - floor);
+
+        // All (charge, discharge) pairs worth more than the spread, best first
+        let mut pairs: Vec<(usize, usize, f64)> = Vec::new();
+        for c in 0..blocks.len() {
+            for d in 0..blocks.len() {
+                let spread = blocks[d].price * eff - blocks[c].price / eff;
+                if c != d && spread > self.config.min_spread_eur_mwh {
+                    pairs.push((c, d, spread));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        for (c, d, _) in pairs {
+            if throughput_left <= 1e-9 {
+                break;
+            }
+            // A block is either charged or discharged, never both
+            if energy[c] > 0.0 || energy[d] < 0.0 {
+                continue;
+            }
+
+            // Battery-side energy x moved from block c to block d
+            let block_kwh =
+                |b: usize| self.config.max_power_kw * blocks[b].ticks as f64 * ctx.timestep_h;
+            let charge_room = (block_kwh(c) + energy[c]) * eff;
+            let discharge_room = (block_kwh(d) - energy[d]) / eff;
+            let soc_room = if c < d {
+                (c..d)
+                    .map(|k| ceiling - soc_after[k])
+                    .fold(f64::INFINITY, f64::min)
+            } else {
+                (d..c)
+                    .map(|k| soc_after[k] - floor)
+                    .fold(f64::INFINITY, f64::min)
+            };
+
+            let x = charge_room
+                .min(discharge_room)
+                .min(soc_room)
+                .min(throughput_left)
+                .max(0.0);
+            if x <= 1e-9 {
+                continue;
+            }
+
+            energy[c] -= x / eff;
+            energy[d] += x * eff;
+            throughput_left -= x;
+            if c < d {
+                (c..d).for_each(|k| soc_after[k] += x);
+            } else {
+                (d..c).for_each(|k| soc_after[k] -= x);
+            }
+        }
+
+        // Spread every block evenly over its ticks
+        for (block, e) in blocks.iter().zip(energy) {
+            for tick in block.first_tick..block.first_tick + block.ticks {
+                self.plan_kwh[tick] = e / block.ticks as f64;
+            }
+        }
+    }
+}
+
+impl DispatchStrategy for Arbitrage {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        let mut setpoints = self.base.dispatch(tick, battery, ctx);
+
+        let today = tick.timestamp.date_naive();
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.plan_day(battery, ctx);
+        }
+
+        let planned_kwh = self.plan_kwh.get(ctx.tick_of_day).copied().unwrap_or(0.0);
+        setpoints.arbitrage_discharge_kwh = planned_kwh.max(0.0);
+        setpoints.arbitrage_charge_kwh = (-planned_kwh).max(0.0);
+        setpoints
+    }
+}
@@ -0,0 +1,107 @@
+pub mod adaptive_peak_shaving;
+pub mod arbitrage;
+pub mod mpc;
+pub mod schedule;
+pub mod self_consumption;
+pub mod srl_peak_shaving;
+pub mod time_of_use;
+
+use super::config::SimulationConfig;
+use crate::model::mergedseries::MergedTick;
+use adaptive_peak_shaving::AdaptivePeakShaving;
+use anyhow::{Result, bail};
+use arbitrage::Arbitrage;
+use mpc::Mpc;
+use self_consumption::SelfConsumption;
+use srl_peak_shaving::SrlPeakShaving;
+use time_of_use::TimeOfUse;
+
+/// Fleet state a strategy sees at the start of its tick (after auxiliary load and transformer relief).
+pub struct BatteryState {
+    pub soc_kwh: f64,
+    pub soc_min_kwh: f64,
+    pub soc_max_kwh: f64,       // faded capacity
+    pub reserve_pos_kwh: f64,   // SRL pos reserve above soc_min
+    pub reserve_neg_kwh: f64,   // SRL neg reserve below soc_max
+    pub max_discharge_kwh: f64, // what the fleet can still deliver this tick (grid side)
+    pub max_charge_kwh: f64,    // what the fleet can still absorb this tick (grid side)
+    pub outside_reserve_discharge_kwh: f64, // the same for every service but SRL
+    pub outside_reserve_charge_kwh: f64,
+}
+
+/// Everything besides the battery a strategy may base its decision on.
+pub struct DispatchContext<'a> {
+    pub config: &'a SimulationConfig,
+    pub history: &'a [MergedTick], // ticks before the current one, never the future
+    pub timestep_h: f64,
+    pub load_kw: f64, // site load left after transformer relief
+    pub aux_grid_kw: f64, // auxiliary load the grid covers in this tick
+    pub spot_prices_today: &'a [Option<f64>], // day-ahead prices of the current day, per tick
+    pub tick_of_day: usize,                     // index of the current tick in spot_prices_today
+}
+
+/// Grid-side energy per service the strategy asks for in this tick, all >= 0.
+///
+/// The engine serves them in priority order and within the transformer limit;
+/// whatever the fleet cannot deliver is reported as unmet.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Setpoints {
+    pub srl_pos_kwh: f64, // deliver
+    pub srl_neg_kwh: f64, // absorb
+    pub ps_discharge_kwh: f64,
+    pub ps_charge_kwh: f64,
+    pub tou_discharge_kwh: f64, // time-of-use shifting against the tariff calendar
+    pub tou_charge_kwh: f64,
+    pub arbitrage_discharge_kwh: f64,
+    pub arbitrage_charge_kwh: f64,
+}
+
+impl Setpoints {
+    /// No service asks for the battery this tick.
+    pub fn is_idle(&self) -> bool {
+        self.srl_pos_kwh == 0.0
+            && self.srl_neg_kwh == 0.0
+            && self.ps_discharge_kwh == 0.0
+            && self.ps_charge_kwh == 0.0
+            && self.tou_discharge_kwh == 0.0
+            && self.tou_charge_kwh == 0.0
+            && self.arbitrage_discharge_kwh == 0.0
+            && self.arbitrage_charge_kwh == 0.0
+    }
+}
+
+/// Operating strategy of the battery: decides per tick how much each service gets.
+pub trait DispatchStrategy {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints;
+}
+
+/// Builds the strategy named in `config.strategy`.
+pub fn build_strategy(config: &SimulationConfig) -> Result<Box<dyn DispatchStrategy>> {
+    match config.strategy.as_str() {
+        "srl_peak_shaving" => Ok(Box::new(SrlPeakShaving)),
+        "arbitrage" => Ok(Box::new(Arbitrage::new(
+            &config.arbitrage,
+            config.efficiency(),
+        ))),
+        "self_consumption" => Ok(Box::new(SelfConsumption)),
+        "adaptive_peak_shaving" => Ok(Box::new(AdaptivePeakShaving::new(
+            &config.adaptive_peak_shaving,
+            config.efficiency(),
+        ))),
+        "time_of_use" => match &config.tariff {
+            Some(tariff) => Ok(Box::new(TimeOfUse::new(
+                &config.time_of_use,
+                tariff,
+                config.efficiency(),
+            ))),
+            None => bail!("Strategy time_of_use needs a tariff calendar"),
+        },
+        "mpc" => Ok(Box::new(Mpc::new(config))),
+        other => bail!("Unknown dispatch strategy: {}", other),
+    }
+}
@@ -0,0 +1,261 @@
+use super::srl_peak_shaving::SrlPeakShaving;
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+use crate::simulation::config::SimulationConfig;
+use crate::simulation::engine::{SimulationRun, run_simulation_with};
+use crate::simulation::forecast::{ForecastConfig, forecast_ticks};
+use crate::simulation::optimizer::{BlockPlan, FleetLimits, plan_horizon};
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MpcConfig {
+    pub reoptimize_minutes: f64, // block length; only the first block of each plan is applied
+    pub horizon_hours: f64,
+    pub forecast: ForecastConfig,
+}
+
+impl Default for MpcConfig {
+    fn default() -> Self {
+        Self {
+            reoptimize_minutes: 15.0,
+            horizon_hours: 24.0,
+            forecast: ForecastConfig::default(),
+        }
+    }
+}
+
+/// What the MPC planned for one tick, from the forecast at the time of planning.
+struct PlannedTick {
+    forecast_load_kw: f64,
+    battery_kw: f64, // peak shaving, + discharge
+    site_kw: f64,    // SRL, auxiliary load from the grid and transformer relief, + import
+}
+
+/// Rolling-horizon model predictive control.
+///
+/// Every `reoptimize_minutes` the LP of the optimiser is solved over the forecast of the
+/// next `horizon_hours`, starting from the actual SoC and the month's peak so far. Only
+/// the first block is applied; where the measured load would exceed the planned peak,
+/// the battery discharges more. SRL activations are followed as they come.
+pub struct Mpc {
+    base: SrlPeakShaving,
+    efficiency: f64, // one way, to turn SoC into grid energy
+    ticks_per_block: usize,
+    horizon_ticks: usize,
+    forecast: ForecastConfig,
+    block: Option<BlockPlan>,
+    block_left: usize,          // ticks of the current block still to apply
+    forecast_load_kw: Vec<f64>, // of the current block
+    peak_target_kw: f64,        // planned grid peak of the month
+    month_peaks_kw: HashMap<(i32, u32), f64>, // grid peaks so far, estimated
+    planned: Vec<PlannedTick>,
+    solves: usize,
+    fallbacks: usize, // blocks where the LP failed and the default strategy ran
+}
+
+impl Mpc {
+    pub fn new(config: &SimulationConfig) -> Self {
+        let mpc = &config.mpc;
+        let ticks = |minutes: f64| ((minutes / config.timestep_minutes).round() as usize).max(1);
+
+        Self {
+            base: SrlPeakShaving,
+            efficiency: config.efficiency(),
+            ticks_per_block: ticks(mpc.reoptimize_minutes),
+            horizon_ticks: ticks(mpc.horizon_hours * 60.0),
+            forecast: mpc.forecast.clone(),
+            block: None,
+            block_left: 0,
+            forecast_load_kw: Vec::new(),
+            peak_target_kw: 0.0,
+            month_peaks_kw: HashMap::new(),
+            planned: Vec::new(),
+            solves: 0,
+            fallbacks: 0,
+        }
+    }
+
+    fn replan(&mut self, tick: &MergedTick, battery: &BatteryState, ctx: &DispatchContext) {
+        let forecast = forecast_ticks(
+            ctx.history,
+            tick,
+            self.horizon_ticks.max(self.ticks_per_block),
+            &self.forecast,
+            ctx.config.timestep_minutes,
+        );
+        let limits = FleetLimits {
+            floor_kwh: battery.soc_min_kwh + battery.reserve_pos_kwh,
+            ceiling_kwh: battery.soc_max_kwh - battery.reserve_neg_kwh,
+            ..FleetLimits::nominal(ctx.config)
+        };
+
+        self.solves += 1;
+        self.block_left = self.ticks_per_block;
+        self.forecast_load_kw = forecast[..self.ticks_per_block]
+            .iter()
+            .map(|t| t.power_kw)
+            .collect();
+
+        match plan_horizon(
+            &forecast,
+            self.ticks_per_block,
+            ctx.config,
+            &limits,
+            battery.soc_kwh,
+            &self.month_peaks_kw,
+        ) {
+            Ok(plan) => {
+                self.block = plan.blocks.first().copied();
+                self.peak_target_kw = plan
+                    .month_peaks_kw
+                    .get(&month(tick.timestamp))
+                    .copied()
+                    .unwrap_or(f64::INFINITY);
+            }
+            Err(_) => {
+                self.block = None;
+                self.fallbacks += 1;
+            }
+        }
+    }
+}
+
+fn month(timestamp: DateTime<Utc>) -> (i32, u32) {
+    (timestamp.year(), timestamp.month())
+}
+
+impl DispatchStrategy for Mpc {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        if self.block_left == 0 {
+            self.replan(tick, battery, ctx);
+        }
+        let forecast_load_kw = self.forecast_load_kw[self.ticks_per_block - self.block_left];
+        self.block_left -= 1;
+
+        let mut setpoints = self.base.dispatch(tick, battery, ctx);
+        // Known at dispatch, like in the engine's grid power: SRL, aux and relief
+        let srl_kw = (setpoints.srl_neg_kwh - setpoints.srl_pos_kwh) / ctx.timestep_h;
+        let site_kw = srl_kw + ctx.aux_grid_kw + ctx.load_kw - tick.power_kw;
+        let Some(block) = self.block else {
+            // No plan: the default strategy keeps the site safe
+            self.planned.push(PlannedTick {
+                forecast_load_kw,
+                battery_kw: 0.0,
+                site_kw,
+            });
+            return setpoints;
+        };
+
+        // First block of the plan, more discharge where the grid beats the planned peak
+        let planned_kw = block.discharge_kw - block.charge_kw;
+        let mut battery_kw = planned_kw.max(tick.power_kw + site_kw - self.peak_target_kw);
+        if battery_kw > 0.0 {
+            battery_kw = battery_kw.min(ctx.load_kw.max(0.0)); // no export
+        }
+        setpoints.ps_discharge_kwh = battery_kw.max(0.0) * ctx.timestep_h;
+        setpoints.ps_charge_kwh = (-battery_kw).max(0.0) * ctx.timestep_h;
+
+        // Estimated grid peak: what the battery can deliver of the request
+        let deliverable_kw = battery.max_discharge_kwh.min(
+            (battery.soc_kwh - battery.soc_min_kwh - battery.reserve_pos_kwh).max(0.0)
+                * self.efficiency,
+        ) / ctx.timestep_h;
+        let grid_kw = tick.power_kw + site_kw - battery_kw.min(deliverable_kw);
+        let peak = self
+            .month_peaks_kw
+            .entry(month(tick.timestamp))
+            .or_insert(0.0);
+        *peak = peak.max(grid_kw);
+
+        self.planned.push(PlannedTick {
+            forecast_load_kw,
+            battery_kw: planned_kw,
+            site_kw,
+        });
+        setpoints
+    }
+}
+
+/// Planned against realised schedule of one tick.
+#[derive(Debug, Serialize)]
+pub struct MpcScheduleTick {
+    pub timestamp: DateTime<Utc>,
+    pub forecast_load_kw: f64,
+    pub load_kw: f64,
+    pub planned_battery_kw: f64, // peak shaving, + discharge
+    pub realised_battery_kw: f64,
+    pub planned_grid_kw: f64, // forecast load, SRL, aux and relief minus planned battery power
+    pub realised_grid_kw: f64,
+}
+
+/// Planned against realised schedule of a whole MPC run.
+pub struct MpcReport {
+    pub schedule: Vec<MpcScheduleTick>,
+    pub solves: usize,
+    pub fallbacks: usize,
+}
+
+/// Runs the MPC and joins its plan with the engine results.
+pub fn run_mpc(ticks: &[MergedTick], config: &SimulationConfig) -> (SimulationRun, MpcReport) {
+    let mut mpc = Mpc::new(config);
+    let run = run_simulation_with(ticks, config, &mut mpc);
+
+    let schedule = run
+        .ticks
+        .iter()
+        .zip(&mpc.planned)
+        .map(|(result, planned)| MpcScheduleTick {
+            timestamp: result.timestamp,
+            forecast_load_kw: planned.forecast_load_kw,
+            load_kw: result.original_power_kw,
+            planned_battery_kw: planned.battery_kw,
+            realised_battery_kw: result.battery_out_kw - result.battery_in_kw,
+            planned_grid_kw: planned.forecast_load_kw + planned.site_kw - planned.battery_kw,
+            realised_grid_kw: result.grid_net_kw,
+        })
+        .collect();
+
+    let report = MpcReport {
+        schedule,
+        solves: mpc.solves,
+        fallbacks: mpc.fallbacks,
+    };
+    (run, report)
+}
+
+impl MpcReport {
+    pub fn print(&self) {
+        let n = self.schedule.len().max(1) as f64;
+        let mae = |f: fn(&MpcScheduleTick) -> f64| {
+            self.schedule.iter().map(|t| f(t).abs()).sum::<f64>() / n
+        };
+        let peak =
+            |f: fn(&MpcScheduleTick) -> f64| self.schedule.iter().map(f).fold(f64::MIN, f64::max);
+
+        println!("\nMPC planned vs realised:");
+        println!(
+            "LP solves: {} ({} failed, default strategy instead)",
+            self.solves, self.fallbacks
+        );
+        println!(
+            "Load forecast MAE: {:.2} kW",
+            mae(|t| t.forecast_load_kw - t.load_kw)
+        );
+        println!(
+            "Battery power MAE (planned vs realised): {:.2} kW",
+            mae(|t| t.planned_battery_kw - t.realised_battery_kw)
+        );
+        println!(
+            "Grid peak planned: {:.2} kW, realised: {:.2} kW",
+            peak(|t| t.planned_grid_kw),
+            peak(|t| t.realised_grid_kw)
+        );
+    }
+}
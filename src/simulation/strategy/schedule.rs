@@ -0,0 +1,40 @@
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+
+/// Replays precomputed setpoints, one per tick (e.g. the optimiser's plan).
+///
+/// Transformer relief is served by the engine before any strategy, so it is taken off
+/// the planned peak shaving energy.
+pub struct Schedule {
+    setpoints: Vec<Setpoints>,
+}
+
+impl Schedule {
+    pub fn new(setpoints: Vec<Setpoints>) -> Self {
+        Self { setpoints }
+    }
+}
+
+impl DispatchStrategy for Schedule {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        _battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        let mut setpoints = self
+            .setpoints
+            .get(ctx.history.len())
+            .copied()
+            .unwrap_or_default();
+
+        let relief_kwh = (tick.power_kw - ctx.load_kw) * ctx.timestep_h;
+        if relief_kwh > 0.0 {
+            setpoints.ps_discharge_kwh = (setpoints.ps_discharge_kwh - relief_kwh).max(0.0);
+        } else {
+            setpoints.ps_charge_kwh = (setpoints.ps_charge_kwh + relief_kwh).max(0.0);
+        }
+
+        setpoints
+    }
+}
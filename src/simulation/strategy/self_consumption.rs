@@ -0,0 +1,25 @@
+use super::srl_peak_shaving::SrlPeakShaving;
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+
+/// Self-consumption: SRL as in the default strategy, the rest of the battery follows
+/// the net load. PV surplus is charged, any remaining load is covered from the battery.
+///
+/// Uses the peak shaving setpoints with a threshold of zero, so the battery stays
+/// outside the SRL reserve.
+pub struct SelfConsumption;
+
+impl DispatchStrategy for SelfConsumption {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        let mut setpoints = SrlPeakShaving.dispatch(tick, battery, ctx);
+
+        setpoints.ps_discharge_kwh = ctx.load_kw.max(0.0) * ctx.timestep_h;
+        setpoints.ps_charge_kwh = (-ctx.load_kw).max(0.0) * ctx.timestep_h;
+        setpoints
+    }
+}
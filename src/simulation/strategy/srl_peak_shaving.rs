@@ -0,0 +1,34 @@
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+
+/// Default strategy: follow every SRL activation, shave load beyond `ps_threshold_kw`.
+pub struct SrlPeakShaving;
+
+impl DispatchStrategy for SrlPeakShaving {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        _battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        let threshold = ctx.config.ps_threshold_kw;
+        let epsilon = 1e-6;
+        let mut setpoints = Setpoints::default();
+
+        // SRL: either positive or negative activation per tick
+        if tick.srl_pos_kwh > 0.0 {
+            setpoints.srl_pos_kwh = tick.srl_pos_kwh;
+        } else if tick.srl_neg_kwh < 0.0 {
+            setpoints.srl_neg_kwh = -tick.srl_neg_kwh;
+        }
+
+        // Peak shaving: discharge above +threshold, charge below -threshold
+        if ctx.load_kw > threshold + epsilon {
+            setpoints.ps_discharge_kwh = (ctx.load_kw - threshold) * ctx.timestep_h;
+        } else if ctx.load_kw < -threshold - epsilon {
+            setpoints.ps_charge_kwh = (-threshold - ctx.load_kw) * ctx.timestep_h;
+        }
+
+        setpoints
+    }
+}
@@ -0,0 +1,68 @@
+use super::srl_peak_shaving::SrlPeakShaving;
+use super::{BatteryState, DispatchContext, DispatchStrategy, Setpoints};
+use crate::model::mergedseries::MergedTick;
+use crate::simulation::tariff::TariffCalendar;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeOfUseConfig {
+    pub charge_power_kw: f64, // grid side, low tariff
+}
+
+impl Default for TimeOfUseConfig {
+    fn default() -> Self {
+        Self {
+            charge_power_kw: 250.0,
+        }
+    }
+}
+
+/// Time-of-use: SRL and peak shaving as in the default strategy. In low-tariff windows the
+/// battery charges up to the SRL reserve, without pushing the load above `ps_threshold_kw`;
+/// in high-tariff windows it covers the site load down to the reserve.
+pub struct TimeOfUse {
+    base: SrlPeakShaving,
+    config: TimeOfUseConfig,
+    tariff: TariffCalendar,
+    efficiency: f64, // one way, to turn the SoC headroom into grid energy
+}
+
+impl TimeOfUse {
+    pub fn new(config: &TimeOfUseConfig, tariff: &TariffCalendar, efficiency: f64) -> Self {
+        Self {
+            base: SrlPeakShaving,
+            config: config.clone(),
+            tariff: tariff.clone(),
+            efficiency,
+        }
+    }
+}
+
+impl DispatchStrategy for TimeOfUse {
+    fn dispatch(
+        &mut self,
+        tick: &MergedTick,
+        battery: &BatteryState,
+        ctx: &DispatchContext,
+    ) -> Setpoints {
+        let mut setpoints = self.base.dispatch(tick, battery, ctx);
+
+        // On top of what peak shaving already asks for
+        if self.tariff.is_high_tariff(tick.timestamp) {
+            setpoints.tou_discharge_kwh =
+                (ctx.load_kw.max(0.0) * ctx.timestep_h - setpoints.ps_discharge_kwh).max(0.0);
+        } else if setpoints.ps_discharge_kwh == 0.0 {
+            let ceiling_kwh = battery.soc_max_kwh - battery.reserve_neg_kwh;
+            let headroom_kwh = (ceiling_kwh - battery.soc_kwh).max(0.0) / self.efficiency;
+            let power_kw = self
+                .config
+                .charge_power_kw
+                .min(ctx.config.ps_threshold_kw - ctx.load_kw)
+                .max(0.0);
+            setpoints.tou_charge_kwh =
+                ((power_kw * ctx.timestep_h).min(headroom_kwh) - setpoints.ps_charge_kwh).max(0.0);
+        }
+
+        setpoints
+    }
+}
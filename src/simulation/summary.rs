@@ -1,7 +1,11 @@
 use super::config::{SimulationConfig, SimulationSummary};
+use super::degradation::SohEntry;
+use super::pv::pv_kpis;
+use super::rainflow::{CycleBin, MonthlyCycles, rainflow};
 use super::tick_result::SimulationTickResult;
-use chrono::Datelike;
-use std::collections::HashMap;
+use super::transformer::ViolationEpisode;
+use chrono::{Datelike, Duration};
+use std::collections::{BTreeMap, HashMap};
 
 pub fn summarize(ticks: &[SimulationTickResult], config: &SimulationConfig) -> SimulationSummary {
     let mut summary = SimulationSummary {
@@ -11,28 +15,113 @@ pub fn summarize(ticks: &[SimulationTickResult], config: &SimulationConfig) -> S
         total_srl_in_kwh: 0.0,
         total_ps_out_kwh: 0.0,
         total_ps_in_kwh: 0.0,
+        total_tou_out_kwh: 0.0,
+        total_tou_in_kwh: 0.0,
+        total_arbitrage_out_kwh: 0.0,
+        total_arbitrage_in_kwh: 0.0,
+        total_recovery_in_kwh: 0.0,
+        total_recovery_out_kwh: 0.0,
+
+        total_srl_unmet_kwh: 0.0,
+        total_ps_unmet_kwh: 0.0,
+        total_srl_ramp_shortfall_kwh: 0.0,
+        total_ps_ramp_shortfall_kwh: 0.0,
+
+        total_reactive_compensation_kvarh: 0.0,
+
+        total_self_discharge_kwh: 0.0,
+        total_aux_kwh: 0.0,
+        total_aux_grid_kwh: 0.0,
+
+        max_cell_temp_c: f64::MIN,
+        min_cell_temp_c: f64::MAX,
+        total_hvac_kwh: 0.0,
+        peak_hvac_kw: 0.0,
 
         min_soc_kwh: f64::MAX,
         max_soc_kwh: f64::MIN,
 
         transformer_violations: 0,
+        transformer_excess_kvah: 0.0,
+        peak_grid_apparent_kva: 0.0,
+        transformer_episodes: Vec::new(),
+        peak_transformer_hot_spot_c: None,
+        transformer_loss_of_life_h: 0.0,
+        total_transformer_relief_kwh: 0.0,
+        total_srl_curtailed_kwh: 0.0,
+        total_ps_curtailed_kwh: 0.0,
+
+        competing_ticks: 0,
+        total_opportunity_cost_chf: 0.0,
+
+        pv: None,
 
         total_srl_revenue_chf: 0.0,
         peak_shaving_savings_chf: 0.0,
+        energy_cost_chf: 0.0,
+        energy_cost_without_battery_chf: 0.0,
+        energy_cost_savings_chf: 0.0,
+        arbitrage_revenue_chf: 0.0,
+        recovery_cost_chf: 0.0,
+        aux_cost_chf: 0.0,
+        reactive_savings_chf: 0.0,
         battery_cycles: 0.0,
+        cycle_histogram: Vec::new(),
+        cycles_monthly: Vec::new(),
 
         amortization_years: None,
+
+        final_soh: 1.0,
+        soh_daily: Vec::new(),
+        soh_monthly: Vec::new(),
+        projected_end_of_life: None,
     };
 
     let mut monthly_peak_before: HashMap<(i32, u32), f64> = HashMap::new();
     let mut monthly_peak_after: HashMap<(i32, u32), f64> = HashMap::new();
 
+    // Last SoH value of each day / month
+    let mut daily_soh: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+    let mut monthly_soh: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+
+    let timestep_h = config.timestep_minutes / 60.0;
+
     for tick in ticks {
         summary.total_srl_out_kwh += tick.srl_energy_out_kwh;
         summary.total_srl_in_kwh += tick.srl_energy_in_kwh;
 
-        summary.total_ps_out_kwh += tick.battery_out_kw * (1.0 / 60.0);
-        summary.total_ps_in_kwh += tick.battery_in_kw * (1.0 / 60.0);
+        summary.total_ps_out_kwh += tick.battery_out_kw * timestep_h;
+        summary.total_ps_in_kwh += tick.battery_in_kw * timestep_h;
+
+        summary.total_tou_out_kwh += tick.tou_out_kwh;
+        summary.total_tou_in_kwh += tick.tou_in_kwh;
+
+        summary.total_arbitrage_out_kwh += tick.arbitrage_out_kwh;
+        summary.total_arbitrage_in_kwh += tick.arbitrage_in_kwh;
+        summary.arbitrage_revenue_chf += tick.arbitrage_revenue_chf;
+
+        summary.total_recovery_in_kwh += tick.recovery_in_kwh;
+        summary.total_recovery_out_kwh += tick.recovery_out_kwh;
+        summary.recovery_cost_chf += (tick.recovery_in_kwh - tick.recovery_out_kwh)
+            * config.energy_price_chf_per_kwh(tick.timestamp);
+
+        summary.total_self_discharge_kwh += tick.self_discharge_kwh;
+        summary.total_aux_kwh += tick.aux_load_kw * timestep_h;
+        // Aux energy taken from the battery already shows up as lower service revenue,
+        // only the grid-supplied part is an extra cost
+        let aux_grid_kwh = (tick.aux_load_kw - tick.aux_battery_kw) * timestep_h;
+        summary.total_aux_grid_kwh += aux_grid_kwh;
+        summary.aux_cost_chf += aux_grid_kwh * config.energy_price_chf_per_kwh(tick.timestamp);
+
+        summary.max_cell_temp_c = summary.max_cell_temp_c.max(tick.cell_temp_c);
+        summary.min_cell_temp_c = summary.min_cell_temp_c.min(tick.cell_temp_c);
+        summary.total_hvac_kwh += tick.hvac_kw * timestep_h;
+        summary.peak_hvac_kw = summary.peak_hvac_kw.max(tick.hvac_kw);
+
+        summary.total_srl_unmet_kwh += tick.srl_unmet_pos_kwh + tick.srl_unmet_neg_kwh;
+        summary.total_ps_unmet_kwh += tick.ps_unmet_out_kwh + tick.ps_unmet_in_kwh;
+        summary.total_srl_ramp_shortfall_kwh += tick.srl_ramp_shortfall_kwh;
+        summary.total_ps_ramp_shortfall_kwh += tick.ps_ramp_shortfall_kwh;
 
         summary.min_soc_kwh = summary.min_soc_kwh.min(tick.soc_kwh);
         summary.max_soc_kwh = summary.max_soc_kwh.max(tick.soc_kwh);
@@ -40,6 +129,41 @@ pub fn summarize(ticks: &[SimulationTickResult], config: &SimulationConfig) -> S
         if tick.transformer_violation {
             summary.transformer_violations += 1;
         }
+        summary.transformer_excess_kvah += tick.transformer_excess_kva * timestep_h;
+        summary.peak_grid_apparent_kva = summary.peak_grid_apparent_kva.max(tick.grid_apparent_kva);
+        summary.total_reactive_compensation_kvarh += tick.bess_reactive_kvar.abs() * timestep_h;
+
+        if let Some(hot_spot_c) = tick.transformer_hot_spot_c {
+            summary.peak_transformer_hot_spot_c = Some(
+                summary
+                    .peak_transformer_hot_spot_c
+                    .map_or(hot_spot_c, |p| p.max(hot_spot_c)),
+            );
+        }
+        summary.transformer_loss_of_life_h +=
+            tick.transformer_aging_rate.unwrap_or(0.0) * timestep_h;
+        summary.total_transformer_relief_kwh += tick.transformer_relief_kw.abs() * timestep_h;
+        summary.total_srl_curtailed_kwh += tick.srl_curtailed_kwh;
+        summary.total_ps_curtailed_kwh += tick.ps_curtailed_kwh;
+
+        if tick.services_competing {
+            summary.competing_ticks += 1;
+        }
+        summary.total_opportunity_cost_chf += tick.opportunity_cost_chf;
+
+        if let Some(tariff) = &config.tariff {
+            // Site import incl. time-of-use shifting: SRL, auxiliary load, arbitrage and SoC
+            // recovery are costed separately
+            let price = tariff.price_chf_per_kwh(tick.timestamp);
+            let site_grid_kw = tick.grid_net_kw
+                - (tick.srl_energy_in_kwh - tick.srl_energy_out_kwh) / timestep_h
+                - (tick.aux_load_kw - tick.aux_battery_kw)
+                - (tick.arbitrage_in_kwh - tick.arbitrage_out_kwh) / timestep_h
+                - (tick.recovery_in_kwh - tick.recovery_out_kwh) / timestep_h;
+            summary.energy_cost_chf += site_grid_kw.max(0.0) * timestep_h * price;
+            summary.energy_cost_without_battery_chf +=
+                tick.original_power_kw.max(0.0) * timestep_h * price;
+        }
 
         summary.total_srl_revenue_chf += tick.srl_revenue_pos_chf;
         summary.total_srl_revenue_chf += tick.srl_revenue_neg_chf;
@@ -57,42 +181,123 @@ pub fn summarize(ticks: &[SimulationTickResult], config: &SimulationConfig) -> S
             .entry(key)
             .and_modify(|v| *v = v.max(tick.final_grid_kw))
             .or_insert(tick.final_grid_kw);
+
+        let soh = (tick.soh, tick.resistance_factor);
+        daily_soh.insert(tick.timestamp.format("%Y-%m-%d").to_string(), soh);
+        monthly_soh.insert(format!("{:04}-{:02}", year, month), soh);
+    }
+
+    summary.transformer_episodes = violation_episodes(ticks, config, timestep_h);
+    summary.pv = pv_kpis(ticks, config, timestep_h);
+    summary.soh_daily = to_soh_entries(daily_soh);
+    summary.soh_monthly = to_soh_entries(monthly_soh);
+
+    // Project end of life by extrapolating the average fade rate of this run
+    if let (Some(first), Some(last)) = (ticks.first(), ticks.last()) {
+        summary.final_soh = last.soh;
+
+        // Fleet end-of-life threshold, weighted by nameplate like the SoH itself
+        let eol_soh = config
+            .units
+            .iter()
+            .map(|u| u.degradation.eol_soh * u.capacity_kwh)
+            .sum::<f64>()
+            / config.capacity_kwh();
+        let days = (last.timestamp - first.timestamp).num_minutes() as f64 / 1440.0;
+        let fade_per_day = if days > 0.0 {
+            (1.0 - last.soh) / days
+        } else {
+            0.0
+        };
+
+        if last.soh <= eol_soh {
+            summary.projected_end_of_life = ticks
+                .iter()
+                .find(|t| t.soh <= eol_soh)
+                .map(|t| t.timestamp.date_naive());
+        } else if fade_per_day > 0.0 {
+            let days_left = (last.soh - eol_soh) / fade_per_day;
+            let eol = last.timestamp + Duration::minutes((days_left * 1440.0) as i64);
+            summary.projected_end_of_life = Some(eol.date_naive());
+        }
     }
 
     let mut total_peak_saving_chf = 0.0;
-    let tariff = 10.0; // CHF per kW per month
+    let tariff = config.demand_charge_chf_per_kw_month;
 
     for key in monthly_peak_before.keys() {
         let before = monthly_peak_before.get(key).unwrap_or(&0.0);
         let after = monthly_peak_after.get(key).unwrap_or(&0.0);
 
-        let saved_kw = (before - after).max(0.0);
+        // Negative where SRL, arbitrage or recovery raised the month's peak
+        let saved_kw = before - after;
         let saved_chf = saved_kw * tariff;
         total_peak_saving_chf += saved_chf;
     }
 
-    let usable_capacity = summary.max_soc_kwh - summary.min_soc_kwh;
-
-    if usable_capacity > 0.0 {
-        let total_throughput_kwh = summary.total_ps_out_kwh
-            + summary.total_ps_in_kwh
-            + summary.total_srl_out_kwh
-            + summary.total_srl_in_kwh;
-
-        summary.battery_cycles = total_throughput_kwh / (2.0 * usable_capacity);
-    } else {
-        summary.battery_cycles = 0.0;
+    // Rainflow cycle counting over the SoC trajectory, against nameplate capacity
+    let nameplate_kwh = config.capacity_kwh();
+    let soc_series: Vec<f64> = ticks.iter().map(|t| t.soc_kwh).collect();
+    let cycles = rainflow(&soc_series);
+
+    let bin_count = 10;
+    let bin_width_pct = 100.0 / bin_count as f64;
+    let mut histogram: Vec<CycleBin> = (0..bin_count)
+        .map(|i| CycleBin {
+            depth_from_pct: i as f64 * bin_width_pct,
+            depth_to_pct: (i + 1) as f64 * bin_width_pct,
+            cycles: 0.0,
+            equivalent_full_cycles: 0.0,
+        })
+        .collect();
+    let mut monthly_cycles: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+
+    for cycle in &cycles {
+        let depth_pct = 100.0 * cycle.range / nameplate_kwh;
+        let efc = cycle.count * cycle.range / nameplate_kwh;
+
+        let bin = ((depth_pct / bin_width_pct) as usize).min(bin_count - 1);
+        histogram[bin].cycles += cycle.count;
+        histogram[bin].equivalent_full_cycles += efc;
+
+        let month = ticks[cycle.start].timestamp.format("%Y-%m").to_string();
+        let entry = monthly_cycles.entry(month).or_insert((0.0, 0.0));
+        entry.0 += cycle.count;
+        entry.1 += efc;
+
+        summary.battery_cycles += efc;
     }
 
+    summary.cycle_histogram = histogram;
+    summary.cycles_monthly = monthly_cycles
+        .into_iter()
+        .map(|(month, (cycles, equivalent_full_cycles))| MonthlyCycles {
+            month,
+            cycles,
+            equivalent_full_cycles,
+        })
+        .collect();
+
     summary.peak_shaving_savings_chf = total_peak_saving_chf;
+    summary.energy_cost_savings_chf =
+        summary.energy_cost_without_battery_chf - summary.energy_cost_chf;
 
     // Ammortization
-    let capacity = config.capacity_kwh;
+    let capacity = config.capacity_kwh();
     let price_per_kwh = config.battery_price_per_kwh_chf;
     let invest = capacity * price_per_kwh;
 
     let op_cost = invest * config.operating_cost_rate;
-    let total_revenue = summary.total_srl_revenue_chf + summary.peak_shaving_savings_chf;
+
+    summary.reactive_savings_chf =
+        summary.total_reactive_compensation_kvarh * config.reactive_energy_price_chf_per_kvarh;
+
+    let total_revenue = summary.total_srl_revenue_chf + summary.peak_shaving_savings_chf
+        - summary.aux_cost_chf
+        + summary.reactive_savings_chf
+        + summary.arbitrage_revenue_chf
+        + summary.energy_cost_savings_chf
+        - summary.recovery_cost_chf;
 
     if total_revenue > 0.0 {
         summary.amortization_years = Some((invest + op_cost) / total_revenue);
@@ -103,6 +308,66 @@ pub fn summarize(ticks: &[SimulationTickResult], config: &SimulationConfig) -> S
     summary
 }
 
+/// Groups consecutive violating ticks into episodes.
+fn violation_episodes(
+    ticks: &[SimulationTickResult],
+    config: &SimulationConfig,
+    timestep_h: f64,
+) -> Vec<ViolationEpisode> {
+    let mut episodes: Vec<ViolationEpisode> = Vec::new();
+    let mut open = false;
+    let step = Duration::milliseconds((timestep_h * 3_600_000.0) as i64);
+
+    for tick in ticks {
+        if !tick.transformer_violation {
+            open = false;
+            continue;
+        }
+
+        if !open {
+            episodes.push(ViolationEpisode {
+                start: tick.timestamp,
+                end: tick.timestamp,
+                duration_minutes: 0.0,
+                peak_apparent_kva: 0.0,
+                peak_overload_pct: 0.0,
+                energy_above_limit_kvah: 0.0,
+                peak_hot_spot_c: None,
+            });
+            open = true;
+        }
+
+        if let Some(episode) = episodes.last_mut() {
+            episode.end = tick.timestamp + step;
+            episode.duration_minutes += timestep_h * 60.0;
+            episode.peak_apparent_kva = episode.peak_apparent_kva.max(tick.grid_apparent_kva);
+            episode.peak_overload_pct =
+                100.0 * (episode.peak_apparent_kva / config.transformer_limit_kva - 1.0);
+            episode.energy_above_limit_kvah += tick.transformer_excess_kva * timestep_h;
+            if let Some(hot_spot_c) = tick.transformer_hot_spot_c {
+                episode.peak_hot_spot_c = Some(
+                    episode
+                        .peak_hot_spot_c
+                        .map_or(hot_spot_c, |p| p.max(hot_spot_c)),
+                );
+            }
+        }
+    }
+
+    episodes
+}
+
+fn to_soh_entries(periods: BTreeMap<String, (f64, f64)>) -> Vec<SohEntry> {
+    periods
+        .into_iter()
+        .map(|(period, (soh, resistance_factor))| SohEntry {
+            period,
+            soh,
+            resistance_factor,
+        })
+        .collect()
+}
+
 impl SimulationSummary {
     pub fn print(&self) {
         println!("\n===== Simulation Summary =====\n");
@@ -125,12 +390,123 @@ impl SimulationSummary {
             "PS charge    (in):         {:>8.2} kWh",
             self.total_ps_in_kwh
         );
+        println!(
+            "Time-of-use:               {:>8.2} kWh out, {:.2} kWh in",
+            self.total_tou_out_kwh, self.total_tou_in_kwh
+        );
+        println!(
+            "Arbitrage:                 {:>8.2} kWh out, {:.2} kWh in",
+            self.total_arbitrage_out_kwh, self.total_arbitrage_in_kwh
+        );
+        println!(
+            "SRL unmet:                 {:>8.2} kWh",
+            self.total_srl_unmet_kwh
+        );
+        println!(
+            "PS unmet:                  {:>8.2} kWh",
+            self.total_ps_unmet_kwh
+        );
+        println!(
+            "  thereof ramp-limited:    {:>8.2} kWh SRL, {:.2} kWh PS",
+            self.total_srl_ramp_shortfall_kwh, self.total_ps_ramp_shortfall_kwh
+        );
+        println!(
+            "Self-discharge:            {:>8.2} kWh",
+            self.total_self_discharge_kwh
+        );
+        println!(
+            "Auxiliary load:            {:>8.2} kWh ({:.2} kWh from grid)",
+            self.total_aux_kwh, self.total_aux_grid_kwh
+        );
+        println!(
+            "Cell temperature:  {:>6.1} .. {:>6.1} °C",
+            self.min_cell_temp_c, self.max_cell_temp_c
+        );
+        println!(
+            "HVAC:                      {:>8.2} kWh (peak {:.1} kW)",
+            self.total_hvac_kwh, self.peak_hvac_kw
+        );
         println!("-------------------------------");
         println!(
             "Min SoC: {:>6.1} kWh     Max SoC: {:>6.1} kWh",
             self.min_soc_kwh, self.max_soc_kwh
         );
         println!("Transformer violations:    {}", self.transformer_violations);
+        println!(
+            "  energy above limit:      {:>8.2} kVAh (peak {:.1} kVA at grid)",
+            self.transformer_excess_kvah, self.peak_grid_apparent_kva
+        );
+        if let Some(longest) = self
+            .transformer_episodes
+            .iter()
+            .max_by(|a, b| a.duration_minutes.total_cmp(&b.duration_minutes))
+        {
+            println!(
+                "  episodes:                {:>8} (longest {:.0} min from {}, peak +{:.1} %)",
+                self.transformer_episodes.len(),
+                longest.duration_minutes,
+                longest.start,
+                longest.peak_overload_pct
+            );
+        }
+        if let Some(hot_spot_c) = self.peak_transformer_hot_spot_c {
+            println!(
+                "Transformer hot spot:      {:>8.1} °C peak, loss of life {:.1} h",
+                hot_spot_c, self.transformer_loss_of_life_h
+            );
+        }
+        println!(
+            "Reactive compensation:     {:>8.2} kvarh",
+            self.total_reactive_compensation_kvarh
+        );
+        println!(
+            "Transformer relief:        {:>8.2} kWh",
+            self.total_transformer_relief_kwh
+        );
+        println!(
+            "Curtailed by transformer:  {:>8.2} kWh SRL, {:.2} kWh PS",
+            self.total_srl_curtailed_kwh, self.total_ps_curtailed_kwh
+        );
+        println!(
+            "SRL / PS competing:        {:>8} ticks, opportunity cost {:.2} CHF",
+            self.competing_ticks, self.total_opportunity_cost_chf
+        );
+        if let Some(pv) = &self.pv {
+            println!("===============================\n");
+            println!("Self-consumption (with / without battery)");
+            println!("-------------------------------\n");
+            println!("PV generation:             {:>8.2} kWh", pv.pv_kwh);
+            println!("Consumption:               {:>8.2} kWh", pv.consumption_kwh);
+            println!(
+                "Grid import:               {:>8.2} kWh / {:.2} kWh",
+                pv.import_kwh, pv.import_without_battery_kwh
+            );
+            println!(
+                "Grid export:               {:>8.2} kWh / {:.2} kWh",
+                pv.export_kwh, pv.export_without_battery_kwh
+            );
+            println!(
+                "Self-consumption ratio:    {:>8.1} % / {:.1} %",
+                pv.self_consumption_ratio * 100.0,
+                pv.self_consumption_ratio_without_battery * 100.0
+            );
+            println!(
+                "Autarky rate:              {:>8.1} % / {:.1} %",
+                pv.autarky_rate * 100.0,
+                pv.autarky_rate_without_battery * 100.0
+            );
+            if pv.curtailed_kwh + pv.curtailed_without_battery_kwh > 0.0 {
+                println!("Absorbed above limit:      {:>8.2} kWh", pv.absorbed_kwh);
+                println!(
+                    "PV curtailed:              {:>8.2} kWh / {:.2} kWh",
+                    pv.curtailed_kwh, pv.curtailed_without_battery_kwh
+                );
+                println!(
+                    "Lost feed-in revenue:      {:>8.2} CHF / {:.2} CHF",
+                    pv.lost_feed_in_revenue_chf, pv.lost_feed_in_revenue_without_battery_chf
+                );
+            }
+        }
         println!("===============================\n");
         println!("Economics");
         println!("-------------------------------\n");
@@ -143,9 +519,44 @@ impl SimulationSummary {
             self.peak_shaving_savings_chf
         );
 
+        if self.energy_cost_without_battery_chf > 0.0 {
+            println!(
+                "Energy cost savings (HT/NT): {:>8.2} CHF ({:.2} -> {:.2} CHF)",
+                self.energy_cost_savings_chf,
+                self.energy_cost_without_battery_chf,
+                self.energy_cost_chf
+            );
+        }
+
+        println!(
+            "Auxiliary energy cost:       {:>8.2} CHF",
+            self.aux_cost_chf
+        );
+
+        println!(
+            "Reactive compensation value: {:>8.2} CHF",
+            self.reactive_savings_chf
+        );
+
+        println!(
+            "Arbitrage revenue:           {:>8.2} CHF",
+            self.arbitrage_revenue_chf
+        );
+
+        if self.total_recovery_in_kwh + self.total_recovery_out_kwh > 0.0 {
+            println!(
+                "SoC recovery energy cost:    {:>8.2} CHF ({:.2} kWh in, {:.2} kWh out)",
+                self.recovery_cost_chf, self.total_recovery_in_kwh, self.total_recovery_out_kwh
+            );
+        }
+
         println!(
             "Total revenue:                {:>8.2} CHF",
-            self.total_srl_revenue_chf + self.peak_shaving_savings_chf
+            self.total_srl_revenue_chf + self.peak_shaving_savings_chf - self.aux_cost_chf
+                + self.reactive_savings_chf
+                + self.arbitrage_revenue_chf
+                + self.energy_cost_savings_chf
+                - self.recovery_cost_chf
         );
 
         match self.amortization_years {
@@ -154,7 +565,21 @@ impl SimulationSummary {
         }
 
         println!("================================\n");
-        println!("Estimated battery cycles:   {:>8.2}", self.battery_cycles);
+        println!("Equivalent full cycles:     {:>8.2}", self.battery_cycles);
+        for bin in self.cycle_histogram.iter().filter(|b| b.cycles > 0.0) {
+            println!(
+                "  DoD {:>3.0}-{:>3.0} %:  {:>8.1} cycles  {:>8.2} EFC",
+                bin.depth_from_pct, bin.depth_to_pct, bin.cycles, bin.equivalent_full_cycles
+            );
+        }
+        println!(
+            "State of health (end):      {:>8.2} %",
+            self.final_soh * 100.0
+        );
+        match self.projected_end_of_life {
+            Some(date) => println!("Projected end of life:      {}", date),
+            None => println!("Projected end of life:      not reached"),
+        }
 
         println!("===============================\n");
     }
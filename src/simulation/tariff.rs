@@ -0,0 +1,76 @@
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// High-tariff window, e.g. Mon-Fri 07-20 h. Timestamps are local time like the input data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TariffWindow {
+    pub weekdays: Vec<Weekday>,
+    pub start_hour: u32,  // inclusive
+    pub end_hour: u32,    // exclusive
+    pub months: Vec<u32>, // season, 1 = January; empty = all year
+}
+
+impl TariffWindow {
+    fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        self.weekdays.contains(&timestamp.weekday())
+            && (self.start_hour..self.end_hour).contains(&timestamp.hour())
+            && (self.months.is_empty() || self.months.contains(&timestamp.month()))
+    }
+}
+
+/// Energy tariff with high (HT) and low (NT) tariff windows.
+/// Everything outside the high-tariff windows and all holidays are low tariff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TariffCalendar {
+    pub high_price_chf_per_kwh: f64,
+    pub low_price_chf_per_kwh: f64,
+    pub high_windows: Vec<TariffWindow>,
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl Default for TariffCalendar {
+    fn default() -> Self {
+        let weekdays = vec![
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+
+        Self {
+            high_price_chf_per_kwh: 0.25,
+            low_price_chf_per_kwh: 0.17,
+            high_windows: vec![
+                TariffWindow {
+                    weekdays,
+                    start_hour: 7,
+                    end_hour: 20,
+                    months: Vec::new(),
+                },
+                TariffWindow {
+                    weekdays: vec![Weekday::Sat],
+                    start_hour: 7,
+                    end_hour: 13,
+                    months: Vec::new(),
+                },
+            ],
+            holidays: Vec::new(),
+        }
+    }
+}
+
+impl TariffCalendar {
+    pub fn is_high_tariff(&self, timestamp: DateTime<Utc>) -> bool {
+        !self.holidays.contains(&timestamp.date_naive())
+            && self.high_windows.iter().any(|w| w.contains(timestamp))
+    }
+
+    pub fn price_chf_per_kwh(&self, timestamp: DateTime<Utc>) -> f64 {
+        if self.is_high_tariff(timestamp) {
+            self.high_price_chf_per_kwh
+        } else {
+            self.low_price_chf_per_kwh
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use super::derating::DeratingCurve;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalConfig {
+    pub enabled: bool,
+
+    // Lumped container model
+    pub heat_capacity_kwh_per_k: f64, // thermal mass of cells + container
+    pub ua_kw_per_k: f64,             // heat exchange with ambient through the envelope
+    pub initial_temp_c: f64,
+    pub fallback_ambient_c: f64, // used for ticks without ambient data
+
+    // HVAC
+    pub cooling_setpoint_c: f64,
+    pub heating_setpoint_c: f64,
+    pub cooling_capacity_kw: f64, // thermal
+    pub heating_capacity_kw: f64, // thermal
+    pub cooling_cop: f64,
+    pub heating_cop: f64,
+
+    // Effects of cell temperature
+    pub power_derating: DeratingCurve, // power factor vs cell temperature in °C
+    pub reference_temp_c: f64,
+    pub cold_loss_per_k: f64, // relative loss increase per K below reference_temp_c
+}
+
+impl Default for ThermalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            heat_capacity_kwh_per_k: 2.0,
+            ua_kw_per_k: 0.15,
+            initial_temp_c: 25.0,
+            fallback_ambient_c: 20.0,
+            cooling_setpoint_c: 30.0,
+            heating_setpoint_c: 10.0,
+            cooling_capacity_kw: 20.0,
+            heating_capacity_kw: 10.0,
+            cooling_cop: 3.0,
+            heating_cop: 2.5,
+            power_derating: DeratingCurve {
+                points: vec![(-10.0, 0.2), (5.0, 1.0), (45.0, 1.0), (55.0, 0.0)],
+            },
+            reference_temp_c: 25.0,
+            cold_loss_per_k: 0.02,
+        }
+    }
+}
+
+/// Lumped thermal state of the battery container.
+///
+/// C * dT/dt = losses + UA * (T_ambient - T) - cooling + heating
+///
+/// HVAC is a thermostat: it is decided from the temperature at the start of the tick,
+/// its electric power is part of the auxiliary load of the same tick.
+pub struct ThermalState {
+    config: ThermalConfig,
+    temp_c: f64,
+}
+
+impl ThermalState {
+    pub fn new(config: &ThermalConfig, fallback_temp_c: f64) -> Self {
+        let temp_c = if config.enabled {
+            config.initial_temp_c
+        } else {
+            fallback_temp_c
+        };
+
+        Self {
+            config: config.clone(),
+            temp_c,
+        }
+    }
+
+    pub fn temp_c(&self) -> f64 {
+        self.temp_c
+    }
+
+    /// Power factor for charge/discharge limits at the current cell temperature.
+    pub fn power_factor(&self) -> f64 {
+        if !self.config.enabled {
+            return 1.0;
+        }
+        self.config.power_derating.factor(self.temp_c)
+    }
+
+    /// One-way efficiency with losses rising in the cold.
+    pub fn efficiency(&self, eff: f64) -> f64 {
+        if !self.config.enabled {
+            return eff;
+        }
+        let cold_k = (self.config.reference_temp_c - self.temp_c).max(0.0);
+        (1.0 - (1.0 - eff) * (1.0 + self.config.cold_loss_per_k * cold_k)).max(0.0)
+    }
+
+    /// Thermal HVAC power (+ cooling, - heating) the thermostat asks for in this tick.
+    pub fn hvac_thermal_kw(&self, ambient_temp_c: Option<f64>, timestep_h: f64) -> f64 {
+        let cfg = &self.config;
+        if !cfg.enabled {
+            return 0.0;
+        }
+
+        let ambient = ambient_temp_c.unwrap_or(cfg.fallback_ambient_c);
+        let passive_kw = cfg.ua_kw_per_k * (ambient - self.temp_c);
+        // Power that would bring the container exactly back to the setpoint within one tick
+        let to_setpoint = |setpoint: f64| {
+            cfg.heat_capacity_kwh_per_k * (self.temp_c - setpoint) / timestep_h + passive_kw
+        };
+
+        if self.temp_c > cfg.cooling_setpoint_c {
+            to_setpoint(cfg.cooling_setpoint_c).clamp(0.0, cfg.cooling_capacity_kw)
+        } else if self.temp_c < cfg.heating_setpoint_c {
+            let heating_kw =
+                (-to_setpoint(cfg.heating_setpoint_c)).clamp(0.0, cfg.heating_capacity_kw);
+            -heating_kw
+        } else {
+            0.0
+        }
+    }
+
+    /// Electric power drawn by the HVAC for the given thermal power.
+    pub fn hvac_electric_kw(&self, hvac_thermal_kw: f64) -> f64 {
+        if hvac_thermal_kw > 0.0 {
+            hvac_thermal_kw / self.config.cooling_cop
+        } else {
+            -hvac_thermal_kw / self.config.heating_cop
+        }
+    }
+
+    /// Advances the temperature by one tick.
+    pub fn update(
+        &mut self,
+        losses_kwh: f64,
+        hvac_thermal_kw: f64,
+        ambient_temp_c: Option<f64>,
+        timestep_h: f64,
+    ) {
+        let cfg = &self.config;
+        if !cfg.enabled {
+            return;
+        }
+
+        let ambient = ambient_temp_c.unwrap_or(cfg.fallback_ambient_c);
+        let heat_kw =
+            losses_kwh / timestep_h + cfg.ua_kw_per_k * (ambient - self.temp_c) - hvac_thermal_kw;
+
+        self.temp_c += heat_kw * timestep_h / cfg.heat_capacity_kwh_per_k;
+    }
+}
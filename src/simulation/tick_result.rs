@@ -7,6 +7,9 @@ pub struct SimulationTickResult {
 
     // Inputs
     pub original_power_kw: f64,
+    pub pv_kw: Option<f64>,    // already netted out of original_power_kw
+    pub pv_absorbed_kwh: f64,  // export above the feed-in limit charged into the battery
+    pub pv_curtailed_kwh: f64, // export above the feed-in limit the battery could not take
     pub srl_pos_kwh: f64,
     pub srl_neg_kwh: f64,
 
@@ -18,20 +21,131 @@ pub struct SimulationTickResult {
     pub srl_energy_in_kwh: f64,  // energy absorbed
     pub srl_energy_out_kwh: f64, // energy delivered
 
+    // Time-of-use shifting
+    pub tou_out_kwh: f64,
+    pub tou_in_kwh: f64,
+
+    // Day-ahead arbitrage
+    pub spot_price_eur_mwh: Option<f64>,
+    pub arbitrage_out_kwh: f64,
+    pub arbitrage_in_kwh: f64,
+    pub arbitrage_unmet_kwh: f64, // planned but not delivered
+    pub arbitrage_revenue_chf: f64,
+
+    // SoC recovery toward the target band in idle ticks
+    pub recovery_in_kwh: f64,
+    pub recovery_out_kwh: f64,
+
+    // Requested but not delivered (power or SoC limit)
+    pub srl_unmet_pos_kwh: f64,
+    pub srl_unmet_neg_kwh: f64,
+    pub ps_unmet_out_kwh: f64,
+    pub ps_unmet_in_kwh: f64,
+    pub srl_ramp_shortfall_kwh: f64, // part of unmet caused by ramp limits
+    pub ps_ramp_shortfall_kwh: f64,
+
+    // Net battery power over all services (+ discharge) and ramp hold
+    pub battery_net_kw: f64,
+    pub ramp_hold_kw: f64, // held at the ramp window edge, goes to the grid
+
     // State of Charge
     pub soc_kwh: f64,
     pub soc_percent: f64,
 
+    // Losses and auxiliary consumption
+    pub self_discharge_kwh: f64,
+    pub aux_load_kw: f64,
+    pub aux_battery_kw: f64, // part of aux_load_kw supplied by the battery
+
+    // Thermal
+    pub cell_temp_c: f64,
+    pub hvac_kw: f64, // electric, included in aux_load_kw
+
+    // Power limits at the start of the tick
+    pub battery_charge_limit_kw: f64, // rating, derated (SoC, temperature, battery model)
+    pub battery_discharge_limit_kw: f64,
+    pub inverter_limit_kw: f64,
+    pub p_charge_limit_kw: f64, // effective: min of battery chain and inverter
+    pub p_discharge_limit_kw: f64,
+
+    // Electrical state (equivalent-circuit model only)
+    pub pack_voltage_v: Option<f64>,
+    pub pack_current_a: Option<f64>,
+
+    // Aging
+    pub soh: f64,
+    pub resistance_factor: f64,
+
     // Net power at grid (after all battery + srl)
     pub grid_net_kw: f64,
 
-    // Limit flag
+    // Reactive power (+ inductive, drawn from the grid) and apparent power at the transformer
+    pub site_reactive_kvar: f64,
+    pub bess_reactive_kvar: f64, // supplied by the inverters within their kVA
+    pub grid_reactive_kvar: f64,
+    pub grid_apparent_kva: f64,
+
+    // Transformer limit: flag, residual excess and what enforcing it cost
     pub transformer_violation: bool,
+    pub transformer_excess_kva: f64, // grid_apparent_kva above the limit that remains
+    pub transformer_top_oil_c: Option<f64>, // thermal model only
+    pub transformer_hot_spot_c: Option<f64>,
+    pub transformer_aging_rate: Option<f64>, // relative aging rate V
+    pub transformer_relief_kw: f64, // battery power to relieve the load alone (+ discharge)
+    pub srl_curtailed_kwh: f64,     // part of SRL unmet withheld to respect the limit
+    pub ps_curtailed_kwh: f64,
+    pub tou_curtailed_kwh: f64,
+    pub arbitrage_curtailed_kwh: f64,
 
     // SRL Revenue
     pub srl_revenue_pos_chf: f64,
     pub srl_revenue_neg_chf: f64,
 
+    // SRL and peak shaving competing for the same energy or power
+    pub services_competing: bool,
+    pub opportunity_cost_chf: f64, // value the service served second lost
+
     pub original_grid_kw: f64,
     pub final_grid_kw: f64,
 }
+
+/// State of one battery unit in one tick; `SimulationTickResult` holds the fleet aggregate.
+#[derive(Debug, Serialize)]
+pub struct UnitTickResult {
+    pub timestamp: DateTime<Utc>,
+    pub unit: String,
+
+    // Net battery power over all services (+ discharge) and ramp hold
+    pub battery_net_kw: f64,
+    pub ramp_hold_kw: f64,
+
+    // State of Charge
+    pub soc_kwh: f64,
+    pub soc_min_kwh: f64,
+    pub soc_max_kwh: f64, // faded capacity
+    pub soc_percent: f64,
+
+    // Losses and auxiliary consumption
+    pub self_discharge_kwh: f64,
+    pub aux_load_kw: f64,
+    pub aux_battery_kw: f64,
+
+    // Thermal
+    pub cell_temp_c: f64,
+    pub hvac_kw: f64,
+
+    // Power limits at the start of the tick
+    pub battery_charge_limit_kw: f64,
+    pub battery_discharge_limit_kw: f64,
+    pub inverter_limit_kw: f64,
+    pub p_charge_limit_kw: f64,
+    pub p_discharge_limit_kw: f64,
+
+    // Electrical state (equivalent-circuit model only)
+    pub pack_voltage_v: Option<f64>,
+    pub pack_current_a: Option<f64>,
+
+    // Aging
+    pub soh: f64,
+    pub resistance_factor: f64,
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// IEC 60076-7 thermal model of an oil-immersed transformer (ONAN defaults).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformerThermalConfig {
+    pub enabled: bool,
+
+    // Rated values at K = 1
+    pub rated_top_oil_rise_k: f64,      // Δθ_or over ambient
+    pub rated_hot_spot_gradient_k: f64, // Δθ_hr hot-spot to top-oil
+    pub loss_ratio: f64,                // R: load losses / no-load losses at rated current
+
+    // Exponents and time constants
+    pub oil_exponent: f64,     // x
+    pub winding_exponent: f64, // y
+    pub oil_time_constant_min: f64,
+    pub winding_time_constant_min: f64,
+    pub k11: f64,
+    pub k21: f64,
+    pub k22: f64,
+
+    // Aging of non-thermally upgraded paper: V = 2^((θ_h - reference) / doubling)
+    pub reference_hot_spot_c: f64,
+    pub aging_doubling_k: f64,
+
+    pub fallback_ambient_c: f64, // used for ticks without ambient data
+}
+
+impl Default for TransformerThermalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rated_top_oil_rise_k: 52.0,
+            rated_hot_spot_gradient_k: 26.0,
+            loss_ratio: 6.0,
+            oil_exponent: 0.8,
+            winding_exponent: 1.3,
+            oil_time_constant_min: 210.0,
+            winding_time_constant_min: 10.0,
+            k11: 0.5,
+            k21: 2.0,
+            k22: 2.0,
+            reference_hot_spot_c: 98.0,
+            aging_doubling_k: 6.0,
+            fallback_ambient_c: 20.0,
+        }
+    }
+}
+
+/// Temperatures and aging of one tick.
+pub struct TransformerThermalTick {
+    pub top_oil_c: f64,
+    pub hot_spot_c: f64,
+    pub aging_rate: f64, // relative aging rate V, 1.0 at the reference hot spot
+}
+
+/// Top-oil and hot-spot temperatures as difference equations (IEC 60076-7, Annex C),
+/// driven by the load factor K = S / S_rated and the ambient temperature.
+pub struct TransformerThermalState {
+    config: TransformerThermalConfig,
+    top_oil_rise_k: f64,
+    hot_spot_rise_1_k: f64,
+    hot_spot_rise_2_k: f64,
+}
+
+impl TransformerThermalState {
+    pub fn new(config: &TransformerThermalConfig) -> Self {
+        // Start in steady state at no load
+        let top_oil_rise_k = (1.0 / (1.0 + config.loss_ratio)).powf(config.oil_exponent)
+            * config.rated_top_oil_rise_k;
+
+        Self {
+            config: config.clone(),
+            top_oil_rise_k,
+            hot_spot_rise_1_k: 0.0,
+            hot_spot_rise_2_k: 0.0,
+        }
+    }
+
+    /// Advances the model by one tick; `None` if the model is disabled.
+    pub fn update(
+        &mut self,
+        load_factor: f64,
+        ambient_temp_c: Option<f64>,
+        timestep_h: f64,
+    ) -> Option<TransformerThermalTick> {
+        let cfg = &self.config;
+        if !cfg.enabled {
+            return None;
+        }
+
+        let ambient_c = ambient_temp_c.unwrap_or(cfg.fallback_ambient_c);
+        let dt_min = timestep_h * 60.0;
+        let k = load_factor.max(0.0);
+
+        // Top-oil rise
+        let ultimate_oil_rise_k = ((1.0 + k * k * cfg.loss_ratio) / (1.0 + cfg.loss_ratio))
+            .powf(cfg.oil_exponent)
+            * cfg.rated_top_oil_rise_k;
+        self.top_oil_rise_k += dt_min / (cfg.k11 * cfg.oil_time_constant_min)
+            * (ultimate_oil_rise_k - self.top_oil_rise_k);
+
+        // Hot-spot rise: fast winding term minus slower oil-flow term
+        let gradient_k = cfg.rated_hot_spot_gradient_k * k.powf(cfg.winding_exponent);
+        self.hot_spot_rise_1_k += dt_min / (cfg.k22 * cfg.winding_time_constant_min)
+            * (cfg.k21 * gradient_k - self.hot_spot_rise_1_k);
+        self.hot_spot_rise_2_k += dt_min / (cfg.oil_time_constant_min / cfg.k22)
+            * ((cfg.k21 - 1.0) * gradient_k - self.hot_spot_rise_2_k);
+
+        let top_oil_c = ambient_c + self.top_oil_rise_k;
+        let hot_spot_c = top_oil_c + self.hot_spot_rise_1_k - self.hot_spot_rise_2_k;
+        let aging_rate = 2f64.powf((hot_spot_c - cfg.reference_hot_spot_c) / cfg.aging_doubling_k);
+
+        Some(TransformerThermalTick {
+            top_oil_c,
+            hot_spot_c,
+            aging_rate,
+        })
+    }
+}
+
+/// Consecutive ticks above the transformer limit.
+#[derive(Debug, Serialize)]
+pub struct ViolationEpisode {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>, // end of the last violating tick
+    pub duration_minutes: f64,
+    pub peak_apparent_kva: f64,
+    pub peak_overload_pct: f64, // above the limit
+    pub energy_above_limit_kvah: f64,
+    pub peak_hot_spot_c: Option<f64>, // only with the thermal model
+}
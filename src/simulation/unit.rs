@@ -0,0 +1,304 @@
+use super::auxiliary::{AuxiliaryLoad, AuxiliarySupply};
+use super::battery::{BatteryModel, BatteryModelConfig, build_battery_model};
+use super::budget::TickBudget;
+use super::degradation::{DegradationConfig, DegradationState};
+use super::derating::DeratingCurve;
+use super::reserve::SrlReserve;
+use super::thermal::{ThermalConfig, ThermalState};
+use super::tick_result::UnitTickResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const HOURS_PER_MONTH: f64 = 730.0;
+
+/// Configuration of one battery unit (container) behind the grid connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryUnitConfig {
+    pub name: String,
+    pub capacity_kwh: f64,
+    pub charge_c_rate: f64,    // battery charge rating, e.g. 0.5 = 0.5C
+    pub discharge_c_rate: f64, // battery discharge rating, e.g. 1.0 = 1C
+    pub inverter_kva: f64,     // inverter rating, caps both directions
+    pub efficiency: f64,       // 0.95 → 95%
+    pub min_soc_frac: f64,     // e.g. 0.1
+    pub initial_soc_frac: f64, // e.g. 0.5
+    pub charge_derating: DeratingCurve, // charge power vs SoC (CC/CV taper)
+    pub discharge_derating: DeratingCurve, // discharge power vs SoC
+    pub degradation: DegradationConfig,
+    pub self_discharge_pct_per_month: f64, // e.g. 2.0 = 2% of stored energy per month
+    pub auxiliary_load: AuxiliaryLoad,
+    pub thermal: ThermalConfig,
+    pub battery_model: BatteryModelConfig,
+    pub ramp_up_kw_per_s: Option<f64>, // max increase of net battery power (+ discharge)
+    pub ramp_down_kw_per_s: Option<f64>, // max decrease of net battery power
+}
+
+impl Default for BatteryUnitConfig {
+    fn default() -> Self {
+        Self {
+            name: "unit_1".to_string(),
+            capacity_kwh: 1000.0,
+            charge_c_rate: 1.0,
+            discharge_c_rate: 1.0,
+            inverter_kva: 1000.0,
+            efficiency: 0.95,
+            min_soc_frac: 0.1,
+            initial_soc_frac: 0.5,
+            charge_derating: DeratingCurve::default_charge(),
+            discharge_derating: DeratingCurve::default_discharge(),
+            degradation: DegradationConfig::default(),
+            self_discharge_pct_per_month: 0.0,
+            auxiliary_load: AuxiliaryLoad::Constant { kw: 0.0 },
+            thermal: ThermalConfig::default(),
+            battery_model: BatteryModelConfig::Energy,
+            ramp_up_kw_per_s: None,
+            ramp_down_kw_per_s: None,
+        }
+    }
+}
+
+/// Which part of the SoC window a service may use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServiceBand {
+    Full,           // whole window incl. SRL reserve
+    OutsideReserve, // must leave the SRL reserve untouched
+}
+
+/// State of one battery unit across ticks.
+pub struct BatteryUnit {
+    pub config: BatteryUnitConfig,
+    soc_kwh: f64,
+    degradation: DegradationState,
+    thermal: ThermalState,
+    model: Box<dyn BatteryModel>,
+    prev_net_kw: f64, // net battery power of the previous tick (+ discharge)
+}
+
+/// A unit during one tick: its budget plus everything fixed at the start of the tick.
+pub struct UnitTick {
+    pub budget: TickBudget,
+    soc_start_kwh: f64,
+    soc_min: f64,
+    soc_max: f64,
+    reserve_pos_kwh: f64, // above soc_min, kept for SRL pos
+    reserve_neg_kwh: f64, // below soc_max, kept for SRL neg
+    cell_temp_c: f64,
+    hvac_thermal_kw: f64,
+    hvac_kw: f64,
+    aux_load_kw: f64,
+    aux_battery_kw: f64,
+    battery_charge_limit_kw: f64,
+    battery_discharge_limit_kw: f64,
+    inverter_limit_kw: f64,
+    p_charge_limit_kw: f64,
+    p_discharge_limit_kw: f64,
+}
+
+impl UnitTick {
+    /// Part of the auxiliary load not supplied by the battery.
+    pub fn aux_grid_kw(&self) -> f64 {
+        self.aux_load_kw - self.aux_battery_kw
+    }
+
+    pub fn floor_kwh(&self, band: ServiceBand) -> f64 {
+        match band {
+            ServiceBand::Full => self.soc_min,
+            ServiceBand::OutsideReserve => self.soc_min + self.reserve_pos_kwh,
+        }
+    }
+
+    pub fn ceiling_kwh(&self, band: ServiceBand) -> f64 {
+        match band {
+            ServiceBand::Full => self.soc_max,
+            ServiceBand::OutsideReserve => self.soc_max - self.reserve_neg_kwh,
+        }
+    }
+}
+
+impl BatteryUnit {
+    pub fn new(config: &BatteryUnitConfig) -> Self {
+        let soc_kwh = config.capacity_kwh * config.initial_soc_frac;
+
+        Self {
+            config: config.clone(),
+            soc_kwh,
+            degradation: DegradationState::new(&config.degradation, config.capacity_kwh, soc_kwh),
+            thermal: ThermalState::new(&config.thermal, config.degradation.cell_temp_c),
+            model: build_battery_model(&config.battery_model),
+            prev_net_kw: 0.0,
+        }
+    }
+
+    /// Fixes limits for the coming tick and serves the unit's own auxiliary load.
+    pub fn begin_tick(
+        &mut self,
+        ambient_temp_c: Option<f64>,
+        srl_reserve: &SrlReserve,
+        share: f64, // of the fleet nameplate
+        auxiliary_supply: AuxiliarySupply,
+        timestep_h: f64,
+    ) -> UnitTick {
+        let cfg = &self.config;
+        let timestep_s = timestep_h * 3600.0;
+
+        // Faded capacity and resistance/temperature-dependent efficiency for this tick.
+        // Energy above the faded capacity is lost with it.
+        let soc_min = cfg.capacity_kwh * cfg.min_soc_frac;
+        let soc_max = self.degradation.capacity_kwh();
+        let eff = self
+            .thermal
+            .efficiency(self.degradation.efficiency(cfg.efficiency));
+        let (reserve_pos_kwh, reserve_neg_kwh) =
+            srl_reserve.unit_kwh(soc_max - soc_min, share, eff);
+        self.soc_kwh = self.soc_kwh.min(soc_max);
+
+        // Battery chain: rating, derated at the SoC and cell temperature the tick starts with.
+        // The battery model may limit further (voltage / current), its limits are DC side.
+        let soc_frac = self.soc_kwh / soc_max;
+        let temp_factor = self.thermal.power_factor();
+        let (model_charge_kw, model_discharge_kw) =
+            self.model
+                .power_limits_kw(self.soc_kwh, soc_max, self.degradation.resistance_factor());
+        let battery_charge_limit_kw = (cfg.capacity_kwh
+            * cfg.charge_c_rate
+            * cfg.charge_derating.factor(soc_frac)
+            * temp_factor)
+            .min(model_charge_kw / eff);
+        let battery_discharge_limit_kw = (cfg.capacity_kwh
+            * cfg.discharge_c_rate
+            * cfg.discharge_derating.factor(soc_frac)
+            * temp_factor)
+            .min(model_discharge_kw * eff);
+
+        // Effective limits: the weaker of battery chain and inverter
+        let inverter_limit_kw = cfg.inverter_kva;
+        let p_charge_limit_kw = battery_charge_limit_kw.min(inverter_limit_kw);
+        let p_discharge_limit_kw = battery_discharge_limit_kw.min(inverter_limit_kw);
+
+        // Ramp limits relative to the previous tick
+        let ramp_min_kw = cfg
+            .ramp_down_kw_per_s
+            .map_or(f64::NEG_INFINITY, |r| self.prev_net_kw - r * timestep_s);
+        let ramp_max_kw = cfg
+            .ramp_up_kw_per_s
+            .map_or(f64::INFINITY, |r| self.prev_net_kw + r * timestep_s);
+
+        let mut budget = TickBudget::new(
+            self.soc_kwh,
+            soc_min,
+            soc_max,
+            p_charge_limit_kw,
+            p_discharge_limit_kw,
+            timestep_h,
+            eff,
+        )
+        .with_ramp_window(ramp_min_kw, ramp_max_kw, timestep_h);
+
+        // Auxiliary consumption (incl. HVAC) comes before any service
+        let hvac_thermal_kw = self.thermal.hvac_thermal_kw(ambient_temp_c, timestep_h);
+        let hvac_kw = self.thermal.hvac_electric_kw(hvac_thermal_kw);
+        let aux_load_kw = cfg.auxiliary_load.power_kw(ambient_temp_c) + hvac_kw;
+        let aux_battery_kw = match auxiliary_supply {
+            AuxiliarySupply::Battery => {
+                budget.discharge(aux_load_kw * timestep_h, soc_min) / timestep_h
+            }
+            AuxiliarySupply::Grid => 0.0,
+        };
+
+        UnitTick {
+            budget,
+            soc_start_kwh: self.soc_kwh,
+            soc_min,
+            soc_max,
+            reserve_pos_kwh,
+            reserve_neg_kwh,
+            cell_temp_c: self.thermal.temp_c(),
+            hvac_thermal_kw,
+            hvac_kw,
+            aux_load_kw,
+            aux_battery_kw,
+            battery_charge_limit_kw,
+            battery_discharge_limit_kw,
+            inverter_limit_kw,
+            p_charge_limit_kw,
+            p_discharge_limit_kw,
+        }
+    }
+
+    /// Books the tick: ramp hold, electrical model, aging, temperature and self-discharge.
+    pub fn finish_tick(
+        &mut self,
+        mut tick: UnitTick,
+        timestamp: DateTime<Utc>,
+        ambient_temp_c: Option<f64>,
+        timestep_h: f64,
+    ) -> UnitTickResult {
+        // Hold the power at the ramp window edge if services dropped faster than allowed
+        let ramp_hold_kw = tick.budget.hold_ramp_window() / timestep_h;
+        let battery_net_kw = tick.budget.net_out_kwh() / timestep_h;
+        self.prev_net_kw = battery_net_kw;
+
+        self.soc_kwh = tick.budget.soc_kwh();
+        let electrical = self.model.step(
+            tick.soc_start_kwh,
+            self.soc_kwh,
+            tick.soc_max,
+            timestep_h,
+            self.degradation.resistance_factor(),
+        );
+        self.thermal.update(
+            tick.budget.losses_kwh(),
+            tick.hvac_thermal_kw,
+            ambient_temp_c,
+            timestep_h,
+        );
+
+        // Self-discharge of the stored energy
+        let self_discharge_kwh = self.soc_kwh * self.config.self_discharge_pct_per_month / 100.0
+            * timestep_h
+            / HOURS_PER_MONTH;
+        self.soc_kwh -= self_discharge_kwh;
+
+        // Aging sees the same SoC trajectory as the reported rainflow count
+        self.degradation.update(
+            tick.soc_start_kwh,
+            self.soc_kwh,
+            timestep_h,
+            tick.cell_temp_c,
+        );
+
+        let soc_percent = 100.0 * (self.soc_kwh - tick.soc_min) / (tick.soc_max - tick.soc_min);
+
+        UnitTickResult {
+            timestamp,
+            unit: self.config.name.clone(),
+
+            battery_net_kw,
+            ramp_hold_kw,
+
+            soc_kwh: self.soc_kwh,
+            soc_min_kwh: tick.soc_min,
+            soc_max_kwh: tick.soc_max,
+            soc_percent,
+
+            self_discharge_kwh,
+            aux_load_kw: tick.aux_load_kw,
+            aux_battery_kw: tick.aux_battery_kw,
+
+            cell_temp_c: tick.cell_temp_c,
+            hvac_kw: tick.hvac_kw,
+
+            battery_charge_limit_kw: tick.battery_charge_limit_kw,
+            battery_discharge_limit_kw: tick.battery_discharge_limit_kw,
+            inverter_limit_kw: tick.inverter_limit_kw,
+            p_charge_limit_kw: tick.p_charge_limit_kw,
+            p_discharge_limit_kw: tick.p_discharge_limit_kw,
+
+            pack_voltage_v: electrical.voltage_v,
+            pack_current_a: electrical.current_a,
+
+            soh: self.degradation.soh(),
+            resistance_factor: self.degradation.resistance_factor(),
+        }
+    }
+}
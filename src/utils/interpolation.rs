@@ -1,6 +1,10 @@
 use chrono::{DateTime, Duration, Utc};
 use crate::model::timeseries::LoadEntry;
 use crate::model::srl::SRLEntry;
+use crate::model::ambient::AmbientEntry;
+use crate::model::reactive::ReactiveEntry;
+use crate::model::spot::SpotPriceEntry;
+use crate::model::pv::PvEntry;
 
 /// Generates a timestamp vector at fixed minute intervals between start and end.
 /// Example: generate_time_grid(t0, t1, 1) → [t0, t0+1min, t0+2min, ..., t1]
@@ -14,7 +18,7 @@ pub fn generate_time_grid(
 
     while current <= end {
         times.push(current);
-        current = current + Duration::minutes(step_minutes);
+        current += Duration::minutes(step_minutes);
     }
 
     times
@@ -121,4 +125,132 @@ pub fn interpolate_srl_to_1min(
 }
 
 
+/// Interpolates an AmbientEntry series (temp_c) to 1-min resolution.
+/// Outside the measured range the nearest value is held instead of falling back to 0 °C.
+pub fn interpolate_ambient_to_1min(
+    input: &[AmbientEntry],
+    target_timestamps: &[DateTime<Utc>],
+) -> Vec<AmbientEntry> {
+    let mut result = Vec::with_capacity(target_timestamps.len());
+
+    for &ts in target_timestamps {
+        let mut prev: Option<&AmbientEntry> = None;
+        let mut next: Option<&AmbientEntry> = None;
+
+        for point in input {
+            if point.timestamp <= ts {
+                prev = Some(point);
+            } else {
+                next = Some(point);
+                break;
+            }
+        }
+
+        let temp_c = match (prev, next) {
+            (Some(p0), Some(p1)) => interpolate_scalar(
+                p0.timestamp, p1.timestamp,
+                p0.temp_c, p1.temp_c,
+                ts
+            ),
+            (Some(p), None) | (None, Some(p)) => p.temp_c,
+            (None, None) => 0.0,
+        };
+
+        result.push(AmbientEntry { timestamp: ts, temp_c });
+    }
 
+    result
+}
+
+
+/// Interpolates a ReactiveEntry series (reactive_kvar) to 1-min resolution.
+/// Outside the measured range the nearest value is held.
+pub fn interpolate_reactive_to_1min(
+    input: &[ReactiveEntry],
+    target_timestamps: &[DateTime<Utc>],
+) -> Vec<ReactiveEntry> {
+    let mut result = Vec::with_capacity(target_timestamps.len());
+
+    for &ts in target_timestamps {
+        let mut prev: Option<&ReactiveEntry> = None;
+        let mut next: Option<&ReactiveEntry> = None;
+
+        for point in input {
+            if point.timestamp <= ts {
+                prev = Some(point);
+            } else {
+                next = Some(point);
+                break;
+            }
+        }
+
+        let reactive_kvar = match (prev, next) {
+            (Some(p0), Some(p1)) => interpolate_scalar(
+                p0.timestamp, p1.timestamp,
+                p0.reactive_kvar, p1.reactive_kvar,
+                ts
+            ),
+            (Some(p), None) | (None, Some(p)) => p.reactive_kvar,
+            (None, None) => 0.0,
+        };
+
+        result.push(ReactiveEntry { timestamp: ts, reactive_kvar });
+    }
+
+    result
+}
+
+
+/// Resamples spot prices to 1-min resolution. A price holds for its whole delivery
+/// period, so the last known price is repeated instead of interpolated.
+/// Minutes before the first price stay without price.
+pub fn resample_spot_to_1min(
+    input: &[SpotPriceEntry],
+    target_timestamps: &[DateTime<Utc>],
+) -> Vec<SpotPriceEntry> {
+    let mut result = Vec::with_capacity(target_timestamps.len());
+    let mut next = 0; // both series are sorted, walk the prices once
+
+    for &ts in target_timestamps {
+        while next < input.len() && input[next].timestamp <= ts {
+            next += 1;
+        }
+
+        if next > 0 {
+            result.push(SpotPriceEntry { timestamp: ts, price_eur_mwh: input[next - 1].price_eur_mwh });
+        }
+    }
+
+    result
+}
+
+
+/// Interpolates a PvEntry series (pv_kw) to 1-min resolution.
+/// Outside the measured range there is no generation.
+pub fn interpolate_pv_to_1min(
+    input: &[PvEntry],
+    target_timestamps: &[DateTime<Utc>],
+) -> Vec<PvEntry> {
+    let mut result = Vec::with_capacity(target_timestamps.len());
+    let mut next = 0; // both series are sorted, walk the measurements once
+
+    for &ts in target_timestamps {
+        while next < input.len() && input[next].timestamp <= ts {
+            next += 1;
+        }
+
+        let pv_kw = match (next.checked_sub(1).map(|i| &input[i]), input.get(next)) {
+            (Some(p0), Some(p1)) => interpolate_scalar(
+                p0.timestamp, p1.timestamp,
+                p0.pv_kw, p1.pv_kw,
+                ts
+            ),
+            (Some(p), None) if p.timestamp == ts => p.pv_kw,
+            _ => 0.0,
+        };
+
+        result.push(PvEntry { timestamp: ts, pv_kw });
+    }
+
+    result
+}
@@ -1,21 +1,48 @@
 use crate::model::timeseries::LoadEntry;
 use crate::model::srl::SRLEntry;
 use crate::model::mergedseries::MergedTick;
+use crate::model::ambient::AmbientEntry;
+use crate::model::reactive::ReactiveEntry;
+use crate::model::spot::SpotPriceEntry;
+use crate::model::pv::PvEntry;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 
+// Optional series (ambient, reactive, pv) must be on the same grid as load; missing ones stay None.
+// With a PV series the load is the gross consumption and power_kw becomes the net load.
+// Spot prices are matched by timestamp since they may start later than the load.
 pub fn merge_1min_series(
     load: &[LoadEntry],
     srl: &[SRLEntry],
+    ambient: Option<&[AmbientEntry]>,
+    reactive: Option<&[ReactiveEntry]>,
+    spot: Option<&[SpotPriceEntry]>,
+    pv: Option<&[PvEntry]>,
 ) -> Vec<MergedTick> {
+    let spot_by_time: HashMap<DateTime<Utc>, f64> = spot
+        .unwrap_or_default()
+        .iter()
+        .map(|p| (p.timestamp, p.price_eur_mwh))
+        .collect();
+
     load.iter()
         .zip(srl.iter())
-        .map(|(l, s)| MergedTick {
-            timestamp: l.timestamp,
-            power_kw: l.power_kw,
-            srl_pos_kwh: s.pos_energy_kwh,
-            srl_neg_kwh: s.neg_energy_kwh,
-            srl_pos_price_eur_mwh: s.pos_price_eur_mwh,
-            srl_neg_price_eur_mwh: s.neg_price_eur_mwh,
+        .enumerate()
+        .map(|(i, (l, s))| {
+            let pv_kw = pv.and_then(|p| p.get(i)).map(|p| p.pv_kw);
+            MergedTick {
+                timestamp: l.timestamp,
+                power_kw: l.power_kw - pv_kw.unwrap_or(0.0),
+                srl_pos_kwh: s.pos_energy_kwh,
+                srl_neg_kwh: s.neg_energy_kwh,
+                srl_pos_price_eur_mwh: s.pos_price_eur_mwh,
+                srl_neg_price_eur_mwh: s.neg_price_eur_mwh,
+                ambient_temp_c: ambient.and_then(|a| a.get(i)).map(|a| a.temp_c),
+                reactive_kvar: reactive.and_then(|r| r.get(i)).map(|r| r.reactive_kvar),
+                spot_price_eur_mwh: spot_by_time.get(&l.timestamp).copied(),
+                pv_kw,
+            }
         })
         .collect()
 }
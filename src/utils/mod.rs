@@ -14,6 +14,15 @@ pub fn file_exists(path: &str) -> bool {
 
 }
 
+// true if `path` was modified after `than`; false if either time is unknown
+pub fn is_newer(path: &str, than: &str) -> bool {
+    let modified = |p: &str| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(path), modified(than)) {
+        (Some(a), Some(b)) => a > b,
+        _ => false,
+    }
+}
+
 // parses dmy timestamps from dym to correct float values
 pub fn parse_timestamp_dmy(cell: &DataType) -> Result<DateTime<Utc>> {
     match cell {